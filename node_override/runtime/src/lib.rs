@@ -41,6 +41,7 @@ use pallet_transaction_payment::CurrencyAdapter;
 
 /// Import the template pallet.
 pub use pallet_template;
+use pallet_evercity_filesign::file::{FileId, FileStruct, H256 as FileHash};
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -264,9 +265,70 @@ impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
-use pallet_evercity_filesign;
+use pallet_evercity_filesign::file::{DuplicateHashPolicy, RemovedSignerPolicy};
+
+parameter_types! {
+	pub const FilesignHashPolicy: DuplicateHashPolicy = DuplicateHashPolicy::Reject;
+	pub const FilesignRemovalPolicy: RemovedSignerPolicy = RemovedSignerPolicy::Invalidate;
+	pub const FilesignHashCheckInterval: BlockNumber = 100;
+	pub const FilesignMaxSigners: u32 = 64;
+}
+
+/// Configure the filesign pallet.
 impl pallet_evercity_filesign::Config for Runtime {
-    type Event = Event;
+	type Event = Event;
+	type AuthorityId = pallet_evercity_filesign::crypto::FilesignAuthId;
+	// only used by FileIdMode::Random, which block authors can influence; the Aura template has
+	// no VRF source, unpredictable IDs come from the commit-reveal FileIdMode::Committed
+	type Randomness = RandomnessCollectiveFlip;
+	type HashPolicy = FilesignHashPolicy;
+	type SigningPublicKey = <Signature as Verify>::Signer;
+	type PayloadSignature = Signature;
+	type SignerRemovalPolicy = FilesignRemovalPolicy;
+	type MaxSigners = FilesignMaxSigners;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type OnFileSigned = ();
+	type OnVersionApproved = ();
+	type HashCheckInterval = FilesignHashCheckInterval;
+	type WeightInfo = pallet_evercity_filesign::weights::SubstrateWeight<Runtime>;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime where Call: From<C> {
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::CreateSignedTransaction<C> for Runtime where Call: From<C> {
+	fn create_transaction<A: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(Call, <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+		use codec::Encode;
+		use sp_runtime::SaturatedConversion;
+
+		let period = BlockHashCount::get() as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+		);
+		let raw_payload = generic::SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| A::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (sp_runtime::MultiAddress::Id(account), signature, extra)))
+	}
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -287,7 +349,8 @@ construct_runtime!(
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
 
-		EvercityFilesign: pallet_evercity_filesign::{ Module, Call, Storage, Event<T> },
+		// Add Config<T> together with a chain spec entry to pre-register files at genesis
+		EvercityFilesign: pallet_evercity_filesign::{Module, Call, Storage, Event<T>, ValidateUnsigned},
 	}
 );
 
@@ -456,6 +519,28 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_evercity_filesign::runtime_api::FilesignApi<Block, AccountId> for Runtime {
+		fn get_file_by_id(id: FileId) -> Option<FileStruct<AccountId>> {
+			EvercityFilesign::get_file_by_id(id)
+		}
+
+		fn find_file_by_hash(hash: FileHash) -> Option<(FileId, u32)> {
+			EvercityFilesign::find_file_by_hash(&hash)
+		}
+
+		fn address_has_signed_the_file(id: FileId, address: AccountId) -> bool {
+			EvercityFilesign::address_has_signed_the_file(id, &address)
+		}
+
+		fn latest_version_is_signed(id: FileId) -> bool {
+			EvercityFilesign::latest_version_is_signed(id)
+		}
+
+		fn signing_payload(id: FileId, version: u32, signer: AccountId) -> Vec<u8> {
+			EvercityFilesign::signing_payload(id, version, &signer)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -490,6 +575,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_evercity_filesign, EvercityFilesign);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -0,0 +1,509 @@
+//! Benchmarks of the filesign pallet
+
+use super::*;
+use crate::file::MAX_ATTRIBUTES;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use frame_support::traits::UnfilteredDispatchable;
+use frame_support::sp_runtime::{traits::TrailingZeroInput, AccountId32, MultiSignature, MultiSigner};
+use frame_support::sp_runtime::app_crypto::sr25519;
+use frame_support::sp_std::vec;
+
+const FILE_ID: FileId = [1u8; 16];
+const OTHER_FILE_ID: FileId = [2u8; 16];
+const COMMITTED_FILE_ID: FileId = [3u8; 16];
+// Upper bound of the storage entries of a file removed by force_delete_file
+const MAX_FILE_ENTRIES: u32 = 1_000;
+
+/// Signs sign_with_payload payloads in benchmarks, implemented for the payload signature of the runtime
+pub trait BenchmarkSignature<AccountId>: Sized {
+    /// Account of a new signing key
+    fn generate() -> AccountId;
+    fn sign(signer: &AccountId, message: &[u8]) -> Self;
+}
+
+impl BenchmarkSignature<AccountId32> for MultiSignature {
+    fn generate() -> AccountId32 {
+        MultiSigner::from(sp_io::crypto::sr25519_generate(KEY_TYPE, None)).into_account()
+    }
+
+    fn sign(signer: &AccountId32, message: &[u8]) -> Self {
+        let public = sr25519::Public::from_raw(*signer.as_ref());
+        sp_io::crypto::sr25519_sign(KEY_TYPE, &public, message)
+            .expect("key is in the benchmark keystore")
+            .into()
+    }
+}
+
+#[cfg(test)]
+impl BenchmarkSignature<u64> for frame_support::sp_runtime::testing::TestSignature {
+    fn generate() -> u64 {
+        1_000
+    }
+
+    fn sign(signer: &u64, message: &[u8]) -> Self {
+        frame_support::sp_runtime::testing::TestSignature(*signer, message.to_vec())
+    }
+}
+
+// Creates a file owned by the caller with the given number of signers, every signer has accepted the invitation
+fn create_file_with_signers<T: Config>(owner: &T::AccountId, signers: u32) -> Vec<T::AccountId> {
+    let accounts: Vec<T::AccountId> = (0..signers).map(|i| account("signer", i, 0)).collect();
+    insert_file::<T>(FILE_ID, owner, &accounts);
+    accounts
+}
+
+fn insert_file<T: Config>(id: FileId, owner: &T::AccountId, signers: &[T::AccountId]) {
+    let location = Some(FileLocation::Url(b"https://example.com/document.pdf".to_vec()));
+    let mut file = FileStruct::new(owner.clone(), id, vec![1u8; 32], &H256::from(id_hash(id)), location);
+    for signer in signers.iter() {
+        file.assign_signer_to_file(signer.clone()).unwrap();
+    }
+    FileByID::<T>::insert(id, file);
+}
+
+fn id_hash(id: FileId) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash[..16].copy_from_slice(&id);
+    hash
+}
+
+// Signs the latest version of FILE_ID by every signer but the given number of last ones
+fn sign_file_except<T: Config>(signers: &[T::AccountId], unsigned: usize) {
+    FileByID::<T>::mutate(FILE_ID, |file| {
+        let file = file.as_mut().unwrap();
+        for signer in signers[..signers.len() - unsigned].iter() {
+            file.sign_latest_version(signer.clone(), None).unwrap();
+        }
+    });
+}
+
+// Commits a file ID secret of the caller in a past block, the most expensive ID mode
+fn committed_id_mode<T: Config>(caller: &T::AccountId) -> FileIdMode {
+    let secret = vec![1u8; MAX_ID_SECRET_LENGTH];
+    IdCommitments::<T>::insert(caller, H256::from(sp_io::hashing::blake2_256(&secret)), (T::BlockNumber::zero(), COMMITTED_FILE_ID));
+    ReservedIds::<T>::insert(COMMITTED_FILE_ID, caller);
+    frame_system::Module::<T>::set_block_number(1u32.into());
+    FileIdMode::Committed(secret)
+}
+
+fn members<T: Config>(count: u32) -> Vec<T::AccountId> {
+    (0..count).map(|i| account("member", i, 0)).collect()
+}
+
+benchmarks! {
+    where_clause { where T::PayloadSignature: BenchmarkSignature<T::AccountId> }
+
+    create_new_file {
+        let caller: T::AccountId = whitelisted_caller();
+        let id_mode = committed_id_mode::<T>(&caller);
+        let location = FileLocation::Url(b"https://example.com/document.pdf".to_vec());
+    }: _(RawOrigin::Signed(caller), vec![1u8; 32], H256::from([1u8; 32]), Some(location), id_mode, false)
+    verify {
+        assert_eq!(1, HashCheckCount::get());
+    }
+
+    commit_file_id {
+        let caller: T::AccountId = whitelisted_caller();
+        let commitment = H256::from([1u8; 32]);
+    }: _(RawOrigin::Signed(caller.clone()), commitment)
+    verify {
+        assert!(IdCommitments::<T>::contains_key(&caller, commitment));
+    }
+
+    create_private_file {
+        let caller: T::AccountId = whitelisted_caller();
+        let id_mode = committed_id_mode::<T>(&caller);
+        let filehash = H256::from([1u8; 32]);
+    }: _(RawOrigin::Signed(caller), H256::from([2u8; 32]), filehash, id_mode, true)
+    verify {
+        assert!(FileByHash::contains_key(filehash));
+    }
+
+    reveal_to {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        FileByID::<T>::mutate(FILE_ID, |file| file.as_mut().unwrap().private = true);
+        let recipient: T::AccountId = account("recipient", 0, 0);
+    }: _(RawOrigin::Signed(caller), FILE_ID, recipient.clone(), vec![1u8; MAX_ENVELOPE_LENGTH])
+    verify {
+        assert!(Envelopes::<T>::contains_key(FILE_ID, &recipient));
+    }
+
+    reveal_salt {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        FileByID::<T>::mutate(FILE_ID, |file| file.as_mut().unwrap().versions[0].salt = SaltState::Revealed(vec![2u8]));
+        // correcting the salt clears a mismatch reported with the previous one
+        Mismatches::<T>::insert(FILE_ID, 0, T::BlockNumber::zero());
+        let salt = vec![1u8; MAX_HASH_SALT_LENGTH];
+    }: _(RawOrigin::Signed(caller), FILE_ID, 0, salt.clone())
+    verify {
+        assert_eq!(SaltState::Revealed(salt), FileByID::<T>::get(FILE_ID).unwrap().versions[0].salt);
+        assert!(!Mismatches::<T>::contains_key(FILE_ID, 0));
+    }
+
+    revoke_reveal {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let recipient: T::AccountId = account("recipient", 0, 0);
+        Envelopes::<T>::insert(FILE_ID, &recipient, vec![1u8; MAX_ENVELOPE_LENGTH]);
+    }: _(RawOrigin::Signed(caller), FILE_ID, recipient.clone())
+    verify {
+        assert!(!Envelopes::<T>::contains_key(FILE_ID, &recipient));
+    }
+
+    create_template {
+        let r in 1 .. MAX_TEMPLATE_ROLES as u32;
+        let caller: T::AccountId = whitelisted_caller();
+        let roles: Vec<Vec<u8>> = (0..r).map(|i| vec![i as u8; MAX_ROLE_LENGTH]).collect();
+    }: _(RawOrigin::Signed(caller), vec![1u8; MAX_TEMPLATE_NAME_LENGTH], roles, SigningPolicy::Threshold(r))
+    verify {
+        assert!(TemplateByID::<T>::contains_key(0));
+    }
+
+    create_from_template {
+        let r in 1 .. MAX_TEMPLATE_ROLES as u32;
+        let caller: T::AccountId = whitelisted_caller();
+        let roles: Vec<Vec<u8>> = (0..r).map(|i| vec![i as u8; MAX_ROLE_LENGTH]).collect();
+        let name = vec![1u8; MAX_TEMPLATE_NAME_LENGTH];
+        TemplateByID::<T>::insert(0, TemplateStruct { owner: caller.clone(), name, roles: roles.clone(), policy: SigningPolicy::All });
+        let assignments: Vec<(Vec<u8>, T::AccountId)> = roles.into_iter().enumerate()
+            .map(|(i, role)| (role, account("signer", i as u32, 0)))
+            .collect();
+        let id_mode = committed_id_mode::<T>(&caller);
+    }: _(RawOrigin::Signed(caller), 0, H256::from([1u8; 32]), id_mode, assignments, false)
+    verify {
+        assert!(FileByHash::contains_key(H256::from([1u8; 32])));
+    }
+
+    create_new_version {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let location = FileLocation::Url(b"https://example.com/document-v2.pdf".to_vec());
+    }: _(RawOrigin::Signed(caller), FILE_ID, vec![2u8; 32], H256::from([2u8; 32]), Some(location), None, false)
+    verify {
+        assert_eq!(2, FileByID::<T>::get(FILE_ID).unwrap().versions.len());
+    }
+
+    supersede_file {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        insert_file::<T>(OTHER_FILE_ID, &caller, &[]);
+    }: _(RawOrigin::Signed(caller), FILE_ID, OTHER_FILE_ID)
+    verify {
+        assert_eq!(Some(OTHER_FILE_ID), SupersededBy::get(FILE_ID));
+    }
+
+    finalize_file {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let signers = create_file_with_signers::<T>(&caller, s);
+        sign_file_except::<T>(&signers, 0);
+    }: _(RawOrigin::Signed(caller), FILE_ID)
+    verify {
+        assert!(FinalizedAt::<T>::contains_key(FILE_ID));
+    }
+
+    // the last missing signature approves the version
+    sign_latest_version {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let signers = create_file_with_signers::<T>(&caller, s);
+        sign_file_except::<T>(&signers, 1);
+        let signer = signers[signers.len() - 1].clone();
+    }: _(RawOrigin::Signed(signer.clone()), FILE_ID)
+    verify {
+        assert!(Module::<T>::address_has_signed_the_file(FILE_ID, &signer));
+    }
+
+    sign_latest_version_on_behalf {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let signers = create_file_with_signers::<T>(&caller, s);
+        sign_file_except::<T>(&signers, 1);
+        let principal = signers[signers.len() - 1].clone();
+        let delegate: T::AccountId = account("delegate", 0, 0);
+        // a delegation for all files is looked up after the file delegation
+        Delegations::<T>::insert(&principal, (delegate.clone(), None::<FileId>), T::BlockNumber::from(100u32));
+    }: _(RawOrigin::Signed(delegate), FILE_ID, principal.clone())
+    verify {
+        assert!(Module::<T>::address_has_signed_the_file(FILE_ID, &principal));
+    }
+
+    sign_with_payload {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let signer = T::PayloadSignature::generate();
+        let mut signers = members::<T>(s - 1);
+        signers.push(signer.clone());
+        insert_file::<T>(FILE_ID, &caller, &signers);
+        sign_file_except::<T>(&signers, 1);
+        let signature = T::PayloadSignature::sign(&signer, &Module::<T>::signing_payload(FILE_ID, 0, &signer));
+    }: _(RawOrigin::None, FILE_ID, 0, signer.clone(), signature)
+    verify {
+        assert_eq!(1, PayloadNonce::<T>::get(&signer));
+    }
+
+    add_delegate {
+        let caller: T::AccountId = whitelisted_caller();
+        let delegate: T::AccountId = account("delegate", 0, 0);
+        // renewal with another expiry appends an expiry entry
+        Delegations::<T>::insert(&caller, (delegate.clone(), Some(FILE_ID)), T::BlockNumber::from(50u32));
+    }: _(RawOrigin::Signed(caller.clone()), delegate.clone(), Some(FILE_ID), T::BlockNumber::from(100u32))
+    verify {
+        assert_eq!(Some(T::BlockNumber::from(100u32)), Delegations::<T>::get(&caller, (delegate, Some(FILE_ID))));
+    }
+
+    remove_delegate {
+        let caller: T::AccountId = whitelisted_caller();
+        let delegate: T::AccountId = account("delegate", 0, 0);
+        Delegations::<T>::insert(&caller, (delegate.clone(), Some(FILE_ID)), T::BlockNumber::from(100u32));
+    }: _(RawOrigin::Signed(caller.clone()), delegate.clone(), Some(FILE_ID))
+    verify {
+        assert!(!Delegations::<T>::contains_key(&caller, (delegate, Some(FILE_ID))));
+    }
+
+    // removing the only missing signer approves the version
+    delete_signer {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let signers = create_file_with_signers::<T>(&caller, s);
+        sign_file_except::<T>(&signers, 1);
+        let signer = signers[signers.len() - 1].clone();
+    }: _(RawOrigin::Signed(caller), FILE_ID, signer.clone())
+    verify {
+        assert!(!Module::<T>::address_is_signer_for_file(FILE_ID, &signer));
+    }
+
+    assign_signer {
+        let s in 1 .. T::MaxSigners::get().saturating_sub(1).max(1);
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let signer: T::AccountId = account("invitee", 0, 0);
+    }: _(RawOrigin::Signed(caller), FILE_ID, signer.clone())
+    verify {
+        assert!(FileByID::<T>::get(FILE_ID).unwrap().invitations.contains(&signer));
+    }
+
+    // the signer is invited directly and through a group
+    accept_invitation {
+        let s in 1 .. T::MaxSigners::get().saturating_sub(2).max(1);
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let signer: T::AccountId = account("invitee", 0, 0);
+        FileByID::<T>::mutate(FILE_ID, |file| {
+            let file = file.as_mut().unwrap();
+            file.invite_signer_to_file(signer.clone()).unwrap();
+            let group = FileGroup { group: 0, members: Vec::new(), invited: vec![signer.clone()], threshold: 1 };
+            file.assign_group_to_file(group).unwrap();
+        });
+    }: _(RawOrigin::Signed(signer.clone()), FILE_ID)
+    verify {
+        assert!(Module::<T>::address_is_signer_for_file(FILE_ID, &signer));
+    }
+
+    decline_invitation {
+        let s in 1 .. T::MaxSigners::get().saturating_sub(2).max(1);
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let signer: T::AccountId = account("invitee", 0, 0);
+        FileByID::<T>::mutate(FILE_ID, |file| {
+            let file = file.as_mut().unwrap();
+            file.invite_signer_to_file(signer.clone()).unwrap();
+            let group = FileGroup { group: 0, members: Vec::new(), invited: vec![signer.clone()], threshold: 1 };
+            file.assign_group_to_file(group).unwrap();
+        });
+    }: _(RawOrigin::Signed(signer.clone()), FILE_ID)
+    verify {
+        assert!(!FileByID::<T>::get(FILE_ID).unwrap().invitations.contains(&signer));
+    }
+
+    create_group {
+        let m in 1 .. MAX_GROUP_MEMBERS as u32;
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller), vec![1u8; MAX_GROUP_NAME_LENGTH], members::<T>(m), m)
+    verify {
+        assert!(GroupByID::<T>::contains_key(0));
+    }
+
+    update_group {
+        let m in 1 .. MAX_GROUP_MEMBERS as u32;
+        let caller: T::AccountId = whitelisted_caller();
+        let group = GroupStruct { owner: caller.clone(), name: vec![1u8; MAX_GROUP_NAME_LENGTH], members: members::<T>(1), threshold: 1 };
+        GroupByID::<T>::insert(0, group);
+    }: _(RawOrigin::Signed(caller), 0, members::<T>(m), m)
+    verify {
+        assert_eq!(m as usize, GroupByID::<T>::get(0).unwrap().members.len());
+    }
+
+    assign_group {
+        let s in 1 .. T::MaxSigners::get().saturating_sub(MAX_GROUP_MEMBERS as u32).max(1);
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let members = members::<T>(MAX_GROUP_MEMBERS as u32);
+        let group = GroupStruct { owner: caller.clone(), name: vec![1u8; MAX_GROUP_NAME_LENGTH], members, threshold: 1 };
+        GroupByID::<T>::insert(0, group);
+    }: _(RawOrigin::Signed(caller), FILE_ID, 0)
+    verify {
+        assert_eq!(1, FileByID::<T>::get(FILE_ID).unwrap().signer_groups.len());
+    }
+
+    delete_group {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let members = members::<T>(MAX_GROUP_MEMBERS as u32);
+        FileByID::<T>::mutate(FILE_ID, |file| {
+            let group = FileGroup { group: 0, members, invited: Vec::new(), threshold: 1 };
+            file.as_mut().unwrap().assign_group_to_file(group).unwrap();
+        });
+    }: _(RawOrigin::Signed(caller), FILE_ID, 0)
+    verify {
+        assert!(FileByID::<T>::get(FILE_ID).unwrap().signer_groups.is_empty());
+    }
+
+    force_transfer_owner {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let new_owner: T::AccountId = account("owner", 0, 0);
+        let origin = T::ForceOrigin::successful_origin();
+    }: {
+        Call::<T>::force_transfer_owner(FILE_ID, new_owner.clone()).dispatch_bypass_filter(origin)?;
+    }
+    verify {
+        assert_eq!(new_owner, FileByID::<T>::get(FILE_ID).unwrap().owner);
+    }
+
+    force_remove_signer {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        let signers = create_file_with_signers::<T>(&caller, s);
+        sign_file_except::<T>(&signers, 1);
+        let signer = signers[signers.len() - 1].clone();
+        let origin = T::ForceOrigin::successful_origin();
+    }: {
+        Call::<T>::force_remove_signer(FILE_ID, signer.clone()).dispatch_bypass_filter(origin)?;
+    }
+    verify {
+        assert!(!Module::<T>::address_is_signer_for_file(FILE_ID, &signer));
+    }
+
+    force_delete_file {
+        let s in 1 .. T::MaxSigners::get();
+        let e in 3 .. MAX_FILE_ENTRIES;
+        let caller: T::AccountId = whitelisted_caller();
+        let signers = create_file_with_signers::<T>(&caller, s);
+        insert_file::<T>(OTHER_FILE_ID, &caller, &[]);
+        FileByHash::insert(H256::from(id_hash(FILE_ID)), (FILE_ID, 0));
+        SupersededBy::insert(FILE_ID, OTHER_FILE_ID);
+        Supersedes::insert(OTHER_FILE_ID, FILE_ID);
+        FinalizedAt::<T>::insert(FILE_ID, T::BlockNumber::zero());
+        SignerRole::<T>::insert(FILE_ID, &signers[0], vec![1u8; MAX_ROLE_LENGTH]);
+        Mismatches::<T>::insert(FILE_ID, 0, T::BlockNumber::zero());
+        // the version, the role and the mismatch are entries too, envelopes make up the rest
+        for i in 3..e {
+            Envelopes::<T>::insert(FILE_ID, account::<T::AccountId>("recipient", i, 0), vec![1u8; MAX_ENVELOPE_LENGTH]);
+        }
+        let origin = T::ForceOrigin::successful_origin();
+    }: {
+        Call::<T>::force_delete_file(FILE_ID, e).dispatch_bypass_filter(origin)?;
+    }
+    verify {
+        assert!(!FileByID::<T>::contains_key(FILE_ID));
+        assert!(!Supersedes::contains_key(OTHER_FILE_ID));
+    }
+
+    // the last free attribute slot is filled
+    set_attribute {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        FileByID::<T>::mutate(FILE_ID, |file| {
+            let file = file.as_mut().unwrap();
+            for i in 1..MAX_ATTRIBUTES {
+                file.set_attribute(vec![i as u8; MAX_ATTRIBUTE_KEY_LENGTH], vec![1u8; MAX_ATTRIBUTE_VALUE_LENGTH]).unwrap();
+            }
+        });
+        let key = vec![0u8; MAX_ATTRIBUTE_KEY_LENGTH];
+    }: _(RawOrigin::Signed(caller), FILE_ID, key.clone(), vec![1u8; MAX_ATTRIBUTE_VALUE_LENGTH])
+    verify {
+        assert!(FileByID::<T>::get(FILE_ID).unwrap().attribute(&key).is_some());
+    }
+
+    clear_attribute {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        FileByID::<T>::mutate(FILE_ID, |file| {
+            let file = file.as_mut().unwrap();
+            for i in 0..MAX_ATTRIBUTES {
+                file.set_attribute(vec![i as u8; MAX_ATTRIBUTE_KEY_LENGTH], vec![1u8; MAX_ATTRIBUTE_VALUE_LENGTH]).unwrap();
+            }
+        });
+        let key = vec![(MAX_ATTRIBUTES - 1) as u8; MAX_ATTRIBUTE_KEY_LENGTH];
+    }: _(RawOrigin::Signed(caller), FILE_ID, key.clone())
+    verify {
+        assert!(FileByID::<T>::get(FILE_ID).unwrap().attribute(&key).is_none());
+    }
+
+    anchor_hash {
+        let caller: T::AccountId = whitelisted_caller();
+        let hash = H256::from([3u8; 32]);
+    }: _(RawOrigin::Signed(caller), hash)
+    verify {
+        assert!(Anchors::<T>::contains_key(hash));
+    }
+
+    anchor_batch {
+        let caller: T::AccountId = whitelisted_caller();
+        let root = H256::from([3u8; 32]);
+    }: _(RawOrigin::Signed(caller), root, 1_000)
+    verify {
+        assert_eq!(1_000, Anchors::<T>::get(root).unwrap().leaves);
+    }
+
+    // the payload signature is checked in validate_unsigned, the call itself doesn't verify it
+    report_mismatch {
+        let s in 1 .. T::MaxSigners::get();
+        let caller: T::AccountId = whitelisted_caller();
+        create_file_with_signers::<T>(&caller, s);
+        let public = T::Public::decode(&mut TrailingZeroInput::new(&[][..])).unwrap();
+        let mut checkers = members::<T>(MAX_HASH_CHECKERS as u32 - 1);
+        checkers.push(public.clone().into_account());
+        HashCheckers::<T>::put(checkers);
+        let signature = <T as SigningTypes>::Signature::decode(&mut TrailingZeroInput::new(&[][..])).unwrap();
+        let payload = MismatchPayload { id: FILE_ID, version: 0, block_number: T::BlockNumber::zero(), public };
+    }: _(RawOrigin::None, payload, signature)
+    verify {
+        assert!(Mismatches::<T>::contains_key(FILE_ID, 0));
+    }
+
+    clear_mismatch {
+        Mismatches::<T>::insert(FILE_ID, 0, T::BlockNumber::zero());
+        let origin = T::ForceOrigin::successful_origin();
+    }: {
+        Call::<T>::clear_mismatch(FILE_ID, 0).dispatch_bypass_filter(origin)?;
+    }
+    verify {
+        assert!(!Mismatches::<T>::contains_key(FILE_ID, 0));
+    }
+
+    set_hash_checkers {
+        let c in 1 .. MAX_HASH_CHECKERS as u32;
+        let origin = T::ForceOrigin::successful_origin();
+    }: {
+        Call::<T>::set_hash_checkers(members::<T>(c)).dispatch_bypass_filter(origin)?;
+    }
+    verify {
+        assert_eq!(c as usize, HashCheckers::<T>::get().len());
+    }
+}
+
+impl_benchmark_test_suite!(Module, crate::mock::new_test_ext(), crate::mock::TestRuntime);
@@ -17,18 +17,272 @@ construct_fixed_hash! {
 }
 
 pub type FileId = [u8; 16];
+pub type GroupId = u32;
+pub type TemplateId = u32;
+
+/// Maximum number of members in a signer group
+pub const MAX_GROUP_MEMBERS: usize = 32;
+/// Maximum number of signer groups assigned to a file
+pub const MAX_FILE_GROUPS: usize = 8;
+/// Maximum length in bytes of a signer group name
+pub const MAX_GROUP_NAME_LENGTH: usize = 64;
+/// Maximum length in bytes of a template name
+pub const MAX_TEMPLATE_NAME_LENGTH: usize = 64;
+/// Maximum number of signer roles in a template
+pub const MAX_TEMPLATE_ROLES: usize = 16;
+/// Maximum length in bytes of a signer role name
+pub const MAX_ROLE_LENGTH: usize = 32;
+
+/// Which signatures make a version approved
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Default, RuntimeDebug)]
+pub enum SigningPolicy {
+    /// Every signer has to sign
+    #[default]
+    All,
+    /// Given number of signers has to sign
+    Threshold(u32),
+}
+
+/// Salted commitment state of a version, a salted filehash is sha2_256(salt ++ document)
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Default, RuntimeDebug)]
+pub enum SaltState {
+    /// Filehash is a plain hash of the document
+    #[default]
+    Unsalted,
+    /// Filehash is salted, the salt is kept secret
+    Committed,
+    /// Filehash is salted, the salt is disclosed
+    Revealed(Vec<u8>),
+}
+
+/// What `create_new_file` does when the file hash is already registered
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum DuplicateHashPolicy {
+    /// Any duplicate is rejected
+    Reject,
+    /// Duplicates are allowed
+    Allow,
+    /// Duplicates are allowed only for the owner of the registered file
+    AllowSameOwner,
+}
+
+/// Off-chain notification sent to the webhook by the off-chain worker
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum Notification<AccountId, BlockNumber> {
+    /// Signer was invited to sign the file
+    SignerInvited(FileId, AccountId),
+    /// Delegation of the principal to the delegate for the file or for all files expired in the block
+    DelegationExpired(AccountId, AccountId, Option<FileId>, BlockNumber),
+}
+
+/// What happens to the signatures of a signer removed from a file
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum RemovedSignerPolicy {
+    /// Signatures in every version are marked as not signed
+    Invalidate,
+    /// Signatures stay in versions as a historical record but don't count anymore
+    KeepHistorical,
+    /// Signer who has signed any version can't be removed
+    BlockRemoval,
+}
+
+/// Maximum number of attempts to generate an unused random file ID
+pub const MAX_RANDOM_ID_ATTEMPTS: u32 = 8;
+
+/// Maximum length in bytes of a salt for deterministic file IDs
+pub const MAX_ID_SALT_LENGTH: usize = 32;
+
+/// Maximum length in bytes of a committed file ID secret
+pub const MAX_ID_SECRET_LENGTH: usize = 64;
+
+/// How the ID of a new file is chosen
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum FileIdMode {
+    /// ID generated by the runtime randomness source
+    Random,
+    /// ID given by the caller
+    Explicit(FileId),
+    /// `blake2_128(owner ++ filehash ++ salt)`, can be computed by clients before submission
+    Deterministic(Vec<u8>),
+    /// Reveal of a secret which `blake2_256` was committed with commit_file_id in an earlier block,
+    /// the ID reserved by the commitment, blake2_128 of the SCALE encoded `(owner, commitment, attempt)`, is used.
+    /// Block authors can't bias it and other accounts can't take it before the reveal
+    Committed(Vec<u8>),
+}
+
+/// Maximum length in bytes of an off-chain location pointer
+pub const MAX_LOCATION_LENGTH: usize = 256;
+
+/// Maximum length in bytes of a revealed salt of a salted filehash
+pub const MAX_HASH_SALT_LENGTH: usize = 64;
+
+/// Maximum length in bytes of an encrypted envelope of a private file
+pub const MAX_ENVELOPE_LENGTH: usize = 1024;
+
+/// Maximum number of key/value attributes per file
+pub const MAX_ATTRIBUTES: usize = 16;
+/// Maximum length in bytes of an attribute key
+pub const MAX_ATTRIBUTE_KEY_LENGTH: usize = 32;
+/// Maximum length in bytes of an attribute value
+pub const MAX_ATTRIBUTE_VALUE_LENGTH: usize = 128;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Off-chain location of the exact bytes of a file version
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum FileLocation {
+    /// IPFS content identifier, CIDv0 (`Qm...`) or base32 CIDv1 (`b...`)
+    Ipfs(Vec<u8>),
+    /// HTTP(S) URL
+    Url(Vec<u8>),
+    /// S3 object key
+    S3(Vec<u8>),
+}
+
+impl FileLocation {
+    // Raw bytes of the pointer
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            FileLocation::Ipfs(v) | FileLocation::Url(v) | FileLocation::S3(v) => v,
+        }
+    }
+
+    // Checks the pointer format, IPFS CIDs are checked against CIDv0 and base32 CIDv1 encodings
+    pub fn is_valid(&self) -> bool {
+        match self {
+            FileLocation::Ipfs(cid) => is_valid_cid(cid),
+            FileLocation::Url(url) => url.starts_with(b"https://") || url.starts_with(b"http://"),
+            FileLocation::S3(key) => !key.is_empty(),
+        }
+    }
+
+    // Host of a URL location, None for other locations and for URLs with user info or an IPv6 host
+    pub fn url_host(&self) -> Option<&[u8]> {
+        let url = match self {
+            FileLocation::Url(url) => url.as_slice(),
+            _ => return None,
+        };
+        let rest = if url.starts_with(b"https://") {
+            &url[8..]
+        } else if url.starts_with(b"http://") {
+            &url[7..]
+        } else {
+            return None;
+        };
+        let authority = rest.split(|c| matches!(c, b'/' | b'?' | b'#')).next()?;
+        if authority.contains(&b'@') || authority.starts_with(b"[") {
+            return None;
+        }
+        match authority.split(|c| *c == b':').next() {
+            Some(host) if !host.is_empty() => Some(host),
+            _ => None,
+        }
+    }
+}
+
+fn is_valid_cid(cid: &[u8]) -> bool {
+    match cid {
+        // CIDv0 is a base58btc encoded sha2-256 multihash
+        [b'Q', b'm', ..] if cid.len() == 46 => cid.iter().all(|c| BASE58_ALPHABET.contains(c)),
+        // CIDv1 with the lowercase base32 multibase prefix
+        [b'b', rest @ ..] if rest.len() >= 8 => rest.iter().all(|c| BASE32_ALPHABET.contains(c)),
+        _ => false,
+    }
+}
 
 #[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
 pub struct SigStruct<AccountId> {
     pub address: AccountId,
     pub signed: bool,
+    /// Account which signed on behalf of `address`, if any
+    pub delegate: Option<AccountId>,
 }
 
 #[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
 pub struct VersionStruct<AccountId> {
     pub tag: Vec<u8>,
     pub filehash: H256,
+    pub location: Option<FileLocation>,
+    /// Index of the version this one is derived from, None for the first version
+    pub parent: Option<u32>,
     pub signatures: Vec<SigStruct<AccountId>>,
+    pub salt: SaltState,
+}
+
+/// Errors of FileStruct mutations, converted to the pallet Error by the module
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum FileError {
+    /// File has no versions
+    NoVersions,
+    /// Address has already signed the latest version
+    AlreadySigned,
+    /// Address is already a signer or is already invited
+    AlreadyAssigned,
+    /// Address is not a signer
+    AddressNotSigner,
+    /// Address has no pending invitation
+    NoInvitation,
+    /// Signer group is already assigned
+    GroupAlreadyAssigned,
+    /// Signer group is not assigned
+    GroupNotAssigned,
+    /// File already has MAX_FILE_GROUPS signer groups
+    TooManyGroups,
+    /// File already has MAX_ATTRIBUTES attributes
+    TooManyAttributes,
+    /// No such attribute
+    AttributeNotFound,
+}
+
+/// Proof-of-existence record of a hash or of a Merkle root of a batch of hashes
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct AnchorStruct<AccountId, BlockNumber> {
+    pub owner: AccountId,
+    pub block: BlockNumber,
+    /// Number of leaves under a Merkle root, 0 for a single anchored hash
+    pub leaves: u32,
+}
+
+/// Named group of accounts filling a single signer slot of a file
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct GroupStruct<AccountId> {
+    pub owner: AccountId,
+    pub name: Vec<u8>,
+    pub members: Vec<AccountId>,
+    /// Number of member signatures which satisfies the group slot
+    pub threshold: u32,
+}
+
+/// Signer group slot of a file, members and threshold are copied from the group on assignment,
+/// later changes of the group don't affect the file
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct FileGroup<AccountId> {
+    pub group: GroupId,
+    /// Members who accepted the invitation, only they can sign
+    pub members: Vec<AccountId>,
+    /// Invited members of the group
+    pub invited: Vec<AccountId>,
+    pub threshold: u32,
+}
+
+impl<AccountId> FileGroup<AccountId> where AccountId: PartialEq {
+    // Checks if the group slot is filled in the given version
+    pub fn is_satisfied(&self, version: &VersionStruct<AccountId>) -> bool {
+        let signed = self.members.iter()
+            .filter(|member| version.signatures.iter().any(|sig| sig.address == **member && sig.signed))
+            .count();
+        signed as u32 >= self.threshold
+    }
+}
+
+/// Document template, role slots are filled with signers when a file is created from it
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct TemplateStruct<AccountId> {
+    pub owner: AccountId,
+    pub name: Vec<u8>,
+    pub roles: Vec<Vec<u8>>,
+    pub policy: SigningPolicy,
 }
 
 /// Main File Domain
@@ -38,17 +292,28 @@ pub struct FileStruct<AccountId> where AccountId: PartialEq {
     pub id: FileId,
     pub versions: Vec<VersionStruct<AccountId>>,
     pub signers: Vec<AccountId>,
+    /// Invited signers, they count only after accepting the invitation
+    pub invitations: Vec<AccountId>,
+    pub signer_groups: Vec<FileGroup<AccountId>>,
+    pub policy: SigningPolicy,
+    pub attributes: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Version tags of a private file are commitments blake2_256(tag ++ salt),
+    /// details are disclosed to chosen accounts in encrypted envelopes
+    pub private: bool,
 }
 
 #[allow(clippy::vec_init_then_push)]
 impl<AccountId> FileStruct<AccountId> where AccountId: PartialEq {
     // Constructor for file
-    pub fn new(owner: AccountId, id: FileId, tag: Vec<u8>, filehash: &H256) -> Self {
+    pub fn new(owner: AccountId, id: FileId, tag: Vec<u8>, filehash: &H256, location: Option<FileLocation>) -> Self {
         let empty_vec = Vec::new();
         let latest_version = VersionStruct {
             tag,
             filehash: *filehash,
+            location,
+            parent: None,
             signatures: empty_vec,
+            salt: SaltState::Unsalted,
         };
 
         let mut versions = Vec::with_capacity(1);
@@ -59,37 +324,207 @@ impl<AccountId> FileStruct<AccountId> where AccountId: PartialEq {
             id,
             versions,
             signers: Vec::new(),
+            invitations: Vec::new(),
+            signer_groups: Vec::new(),
+            policy: SigningPolicy::All,
+            attributes: Vec::new(),
+            private: false,
         }
     }
 
-    // Add a sign to last version of file
-    pub fn sign_latest_version(&mut self, caller: AccountId) {
-        let latest_version = self.versions.last_mut().unwrap();
+    // Adds a new version of file derived from the parent version, returns its index
+    pub fn add_version(&mut self, tag: Vec<u8>, filehash: &H256, location: Option<FileLocation>, parent: u32) -> u32 {
+        self.versions.push(VersionStruct {
+            tag,
+            filehash: *filehash,
+            location,
+            parent: Some(parent),
+            signatures: Vec::new(),
+            salt: SaltState::Unsalted,
+        });
+        (self.versions.len() - 1) as u32
+    }
 
-        // here check if has already signed
-        match latest_version.signatures.iter().position(|sig| sig.address == caller) {
-            Some(_) => {/*new logic can be made in future here*/},
+    // Returns the version and its ancestors up to the first version
+    pub fn version_lineage(&self, version: u32) -> Vec<u32> {
+        let mut lineage = Vec::new();
+        let mut current = Some(version);
+        while let Some(index) = current {
+            match self.versions.get(index as usize) {
+                None => break,
+                Some(vers) => {
+                    lineage.push(index);
+                    // parents always precede children, this guards against malformed data
+                    current = vers.parent.filter(|parent| *parent < index);
+                }
+            }
+        }
+        lineage
+    }
+
+    // Add a sign to last version of file, optionally made by a delegate of the signer
+    pub fn sign_latest_version(&mut self, caller: AccountId, delegate: Option<AccountId>) -> Result<(), FileError> {
+        let latest_version = self.versions.last_mut().ok_or(FileError::NoVersions)?;
+
+        match latest_version.signatures.iter_mut().find(|sig| sig.address == caller) {
+            Some(sig) if sig.signed => return Err(FileError::AlreadySigned),
+            // signature was invalidated before, sign again
+            Some(sig) => {
+                sig.signed = true;
+                sig.delegate = delegate;
+            },
             None => {
-                latest_version.signatures.push(SigStruct{address: caller, signed: true});         
+                latest_version.signatures.push(SigStruct{address: caller, signed: true, delegate});
             }
         }
+        Ok(())
     }
 
     // Assigns a new signer to a file
-    pub fn assign_signer_to_file (&mut self, signer: AccountId) {
-        if !self.signers.iter().any(|x| *x == signer){
-            self.signers.push(signer);
-        }    
+    pub fn assign_signer_to_file (&mut self, signer: AccountId) -> Result<(), FileError> {
+        if self.signers.contains(&signer) {
+            return Err(FileError::AlreadyAssigned);
+        }
+        self.signers.push(signer);
+        Ok(())
+    }
+
+    // Invites a signer to a file
+    pub fn invite_signer_to_file(&mut self, signer: AccountId) -> Result<(), FileError> {
+        if self.signers.contains(&signer) || self.invitations.contains(&signer) {
+            return Err(FileError::AlreadyAssigned);
+        }
+        self.invitations.push(signer);
+        Ok(())
+    }
+
+    // Moves an invited signer to signers
+    pub fn accept_invitation(&mut self, signer: AccountId) -> Result<(), FileError> {
+        self.decline_invitation(&signer)?;
+        self.assign_signer_to_file(signer)
+    }
+
+    // Removes a pending invitation
+    pub fn decline_invitation(&mut self, signer: &AccountId) -> Result<(), FileError> {
+        let index = match self.invitations.iter().position(|a| a == signer) {
+            Some(i) => i,
+            None => return Err(FileError::NoInvitation)
+        };
+        self.invitations.remove(index);
+        Ok(())
     }
 
     // Removes signer from file
-    #[allow(clippy::result_unit_err)]
-    pub fn delete_signer_from_file (&mut self, signer: AccountId) -> Result<(), ()> {
+    pub fn delete_signer_from_file (&mut self, signer: AccountId) -> Result<(), FileError> {
         let index = match self.signers.iter().position(|a| a == &signer) {
             Some(i) => i,
-            None => return Err(())
+            None => return Err(FileError::AddressNotSigner)
         };
         self.signers.remove(index);
         Ok(())
     }
+
+    // Number of signers, pending invitations and members and invited members of signer groups
+    pub fn signer_count(&self) -> usize {
+        self.signers.len() + self.invitations.len()
+            + self.signer_groups.iter().map(|g| g.members.len() + g.invited.len()).sum::<usize>()
+    }
+
+    // Assigns a signer group to a file, fails if the file has MAX_FILE_GROUPS groups
+    pub fn assign_group_to_file(&mut self, group: FileGroup<AccountId>) -> Result<(), FileError> {
+        if self.signer_groups.iter().any(|g| g.group == group.group) {
+            return Err(FileError::GroupAlreadyAssigned);
+        }
+        if self.signer_groups.len() >= MAX_FILE_GROUPS {
+            return Err(FileError::TooManyGroups);
+        }
+        self.signer_groups.push(group);
+        Ok(())
+    }
+
+    // Moves the address from invited to members of every group slot, false if it had no group invitation
+    pub fn accept_group_invitations(&mut self, address: &AccountId) -> bool where AccountId: Clone {
+        let mut accepted = false;
+        for group in self.signer_groups.iter_mut() {
+            if let Some(index) = group.invited.iter().position(|a| a == address) {
+                let member = group.invited.remove(index);
+                group.members.push(member);
+                accepted = true;
+            }
+        }
+        accepted
+    }
+
+    // Removes the address from invited of every group slot, false if it had no group invitation
+    pub fn decline_group_invitations(&mut self, address: &AccountId) -> bool {
+        let mut declined = false;
+        for group in self.signer_groups.iter_mut() {
+            if let Some(index) = group.invited.iter().position(|a| a == address) {
+                group.invited.remove(index);
+                declined = true;
+            }
+        }
+        declined
+    }
+
+    // Removes signer group from file
+    pub fn delete_group_from_file(&mut self, group: GroupId) -> Result<(), FileError> {
+        let index = match self.signer_groups.iter().position(|g| g.group == group) {
+            Some(i) => i,
+            None => return Err(FileError::GroupNotAssigned)
+        };
+        self.signer_groups.remove(index);
+        Ok(())
+    }
+
+    // Checks if the address has a valid signature in any version
+    pub fn signed_any_version(&self, address: &AccountId) -> bool {
+        self.versions.iter().any(|vers| vers.signatures.iter().any(|sig| sig.address == *address && sig.signed))
+    }
+
+    // Marks signatures of the address in every version as not signed
+    pub fn invalidate_signatures(&mut self, address: &AccountId) {
+        for vers in self.versions.iter_mut() {
+            for sig in vers.signatures.iter_mut().filter(|sig| sig.address == *address) {
+                sig.signed = false;
+            }
+        }
+    }
+
+    // Checks if the address has signed the latest version
+    pub fn latest_version_signed_by(&self, address: &AccountId) -> bool {
+        match self.versions.last() {
+            None => false,
+            Some(vers) => vers.signatures.iter().any(|sig| sig.address == *address && sig.signed)
+        }
+    }
+
+    // Returns the value of a file attribute
+    pub fn attribute(&self, key: &[u8]) -> Option<&Vec<u8>> {
+        self.attributes.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    // Sets or overwrites a file attribute, fails if a new key exceeds MAX_ATTRIBUTES
+    pub fn set_attribute(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<(), FileError> {
+        match self.attributes.iter().position(|(k, _)| *k == key) {
+            Some(i) => self.attributes[i].1 = value,
+            None => {
+                if self.attributes.len() >= MAX_ATTRIBUTES {
+                    return Err(FileError::TooManyAttributes);
+                }
+                self.attributes.push((key, value));
+            }
+        }
+        Ok(())
+    }
+
+    // Removes a file attribute
+    pub fn clear_attribute(&mut self, key: &[u8]) -> Result<(), FileError> {
+        let index = match self.attributes.iter().position(|(k, _)| k == key) {
+            Some(i) => i,
+            None => return Err(FileError::AttributeNotFound)
+        };
+        self.attributes.remove(index);
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,39 +1,210 @@
 #![allow(clippy::unused_unit)]
 #![cfg_attr(not(feature = "std"), no_std)]
+#![recursion_limit = "256"]
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]    
 mod tests;
 pub mod file;
+pub mod merkle;
+pub mod runtime_api;
+pub mod weights;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 use crate::sp_api_hidden_includes_decl_storage::hidden_include::traits::Randomness;
 use crate::sp_api_hidden_includes_decl_storage::hidden_include::traits::Get;
-use codec::Encode;
+use codec::{Decode, Encode};
 use frame_support::{
     ensure,
+    Parameter,
+    traits::EnsureOrigin,
     decl_event,
     decl_error, 
     decl_module, 
     decl_storage,
+    debug,
+    weights::Weight,
     dispatch::{
+        DispatchError,
         DispatchResult,
         Vec,
     },
 };
 use frame_system::{
     ensure_signed,
+    ensure_none,
+    offchain::{AppCrypto, CreateSignedTransaction, SendUnsignedTransaction, SignedPayload, Signer, SigningTypes},
+};
+use frame_support::sp_runtime::{
+    KeyTypeId,
+    RuntimeAppPublic,
+    RuntimeDebug,
+    offchain::{http, storage::StorageValueRef, Duration},
+    traits::{IdentifyAccount, Saturating, UniqueSaturatedInto, Verify, Zero},
+    transaction_validity::{
+        InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
+    },
 };
 use frame_support::sp_std::{
+    mem,
+    vec,
     cmp::{
         Eq, 
         PartialEq}, 
 };
-use file::{FileStruct, H256, FileId};
+use file::{
+    AnchorStruct, FileError, FileGroup, FileStruct, FileLocation, FileIdMode, DuplicateHashPolicy, GroupStruct, SigningPolicy,
+    Notification, RemovedSignerPolicy, SaltState,
+    TemplateStruct, H256, FileId, GroupId, TemplateId,
+    MAX_GROUP_MEMBERS, MAX_RANDOM_ID_ATTEMPTS, MAX_ROLE_LENGTH, MAX_TEMPLATE_NAME_LENGTH, MAX_TEMPLATE_ROLES, MAX_GROUP_NAME_LENGTH, MAX_ID_SALT_LENGTH, MAX_ID_SECRET_LENGTH, MAX_LOCATION_LENGTH, MAX_ENVELOPE_LENGTH, MAX_HASH_SALT_LENGTH, MAX_ATTRIBUTE_KEY_LENGTH, MAX_ATTRIBUTE_VALUE_LENGTH,
+};
+pub use weights::WeightInfo;
 
-pub trait Config: frame_system::Config {
+pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+    /// Keys the off-chain worker signs mismatch reports with, e.g. crypto::FilesignAuthId,
+    /// only reports of keys registered with set_hash_checkers are accepted
+    type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+    /// Source of randomness for FileIdMode::Random. `pallet_randomness_collective_flip` can be
+    /// influenced by block authors, callers who need unpredictable IDs use the commit-reveal
+    /// FileIdMode::Committed, which doesn't depend on this source
     type Randomness: frame_support::traits::Randomness<Self::Hash>;
+    /// Policy for registering a file hash that is already known
+    type HashPolicy: Get<DuplicateHashPolicy>;
+    /// Public key of a signer account, used to check pre-signed payloads
+    type SigningPublicKey: IdentifyAccount<AccountId = Self::AccountId>;
+    /// Signature of a pre-signed payload submitted through sign_with_payload
+    type PayloadSignature: Verify<Signer = Self::SigningPublicKey> + Parameter;
+    /// Policy for signatures of signers removed from a file
+    type SignerRemovalPolicy: Get<RemovedSignerPolicy>;
+    /// Maximum number of signers of a file, pending invitations and members of assigned signer groups count too.
+    /// Calls on an existing file are charged for this many signers
+    type MaxSigners: Get<u32>;
+    /// Origin for governance overrides, e.g. EnsureRoot wired to pallet_sudo
+    type ForceOrigin: EnsureOrigin<Self::Origin>;
+    /// Handler called on every signature of a file version
+    type OnFileSigned: OnFileSigned<Self::AccountId>;
+    /// Handler called when a file version becomes fully signed
+    type OnVersionApproved: OnVersionApproved;
+    /// Period in blocks of the off-chain check of documents behind version locations, 0 disables it
+    type HashCheckInterval: Get<Self::BlockNumber>;
+    /// Weights of the calls, weights::SubstrateWeight or weights generated for the runtime
+    type WeightInfo: WeightInfo;
+}
+
+/// Handler for other pallets to react on file signatures
+pub trait OnFileSigned<AccountId> {
+    fn on_file_signed(id: FileId, version: u32, signer: &AccountId);
+}
+
+impl<AccountId> OnFileSigned<AccountId> for () {
+    fn on_file_signed(_id: FileId, _version: u32, _signer: &AccountId) {}
+}
+
+/// Handler for other pallets to react on approved file versions, see Module::latest_version_is_signed
+pub trait OnVersionApproved {
+    fn on_version_approved(id: FileId, version: u32);
+}
+
+impl OnVersionApproved for () {
+    fn on_version_approved(_id: FileId, _version: u32) {}
+}
+
+/// Prefix of payloads signed off chain for sign_with_payload
+pub const SIGNING_PAYLOAD_PREFIX: &[u8] = b"filesign/sign";
+
+/// Key of the notification webhook URL in persistent off-chain local storage, the value is a SCALE encoded Vec<u8>
+pub const WEBHOOK_URL_KEY: &[u8] = b"filesign::webhook-url";
+
+/// Prefix of the off-chain index keys of notifications, the key is (prefix, block, batch) where batch counts
+/// the notification batches indexed in the block from 0, and the value is a SCALE encoded Vec<Notification>
+pub const NOTIFICATIONS_KEY_PREFIX: &[u8] = b"filesign::notifications";
+
+/// Timeout of a webhook request in milliseconds
+pub const WEBHOOK_TIMEOUT_MS: u64 = 3_000;
+
+/// Key of the IPFS gateway URL prefix in persistent off-chain local storage, the value is a SCALE encoded Vec<u8>
+pub const IPFS_GATEWAY_KEY: &[u8] = b"filesign::ipfs-gateway";
+
+/// IPFS gateway used when none is set in off-chain local storage
+pub const DEFAULT_IPFS_GATEWAY: &[u8] = b"https://ipfs.io/ipfs/";
+
+/// Timeout of a document download in milliseconds
+pub const FETCH_TIMEOUT_MS: u64 = 10_000;
+
+/// Key of the hosts URL locations are downloaded from in persistent off-chain local storage, the value is
+/// a SCALE encoded Vec<Vec<u8>>, URL locations are not downloaded while no host is allowed
+pub const FETCH_HOSTS_KEY: &[u8] = b"filesign::fetch-hosts";
+
+/// Prefix of the off-chain index keys of versions queued for a hash check, the key is (prefix, queue position)
+/// and the value is a SCALE encoded (FileId, u32)
+pub const HASH_CHECK_KEY_PREFIX: &[u8] = b"filesign::hash-checks";
+
+/// Key of the hash check cursor in persistent off-chain local storage, the value is a SCALE encoded (u64, u32)
+/// of the queue position of the next check and the failed downloads of that version
+pub const HASH_CHECK_CURSOR_KEY: &[u8] = b"filesign::hash-check-cursor";
+
+/// Maximum number of queued versions checked in a worker run, each check downloads at most one document
+pub const MAX_HASH_CHECKS_PER_RUN: u64 = 8;
+
+/// Number of failed downloads after which a queued version is skipped
+pub const MAX_FETCH_ATTEMPTS: u32 = 3;
+
+/// Key type of the hash checker keys in the node keystore
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"fsgn");
+
+/// Maximum number of registered hash checkers
+pub const MAX_HASH_CHECKERS: usize = 16;
+
+/// Number of blocks a mismatch report is valid for after the block it was signed in
+pub const MISMATCH_REPORT_LONGEVITY: u32 = 64;
+
+/// Maximum number of delegations expiring in the same block, bounds the work of on_initialize
+pub const MAX_EXPIRIES_PER_BLOCK: usize = 64;
+
+/// sr25519 hash checker keys, inserted into the keystore of a checking node with the `fsgn` key type
+pub mod crypto {
+    use super::KEY_TYPE;
+    use frame_support::sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        traits::Verify,
+        MultiSignature, MultiSigner,
+    };
+    app_crypto!(sr25519, KEY_TYPE);
+
+    pub struct FilesignAuthId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for FilesignAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sr25519::Signature;
+        type GenericPublic = sr25519::Public;
+    }
+
+    impl frame_system::offchain::AppCrypto<<sr25519::Signature as Verify>::Signer, sr25519::Signature> for FilesignAuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sr25519::Signature;
+        type GenericPublic = sr25519::Public;
+    }
+}
+
+/// Pre-registered genesis file: id, owner, tag, filehash, signers, signers who have signed the file
+pub type GenesisFile<AccountId> = (FileId, AccountId, Vec<u8>, [u8; 32], Vec<AccountId>, Vec<AccountId>);
+
+/// Mismatch report of the off-chain worker, signed with a hash checker key
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MismatchPayload<Public, BlockNumber> {
+    pub id: FileId,
+    pub version: u32,
+    pub block_number: BlockNumber,
+    pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for MismatchPayload<T::Public, T::BlockNumber> {
+    fn public(&self) -> T::Public {
+        self.public.clone()
+    }
 }
 
 decl_storage! {
@@ -43,24 +214,215 @@ decl_storage! {
             get(fn file_by_id):
             map hasher(blake2_128_concat) FileId => Option<FileStruct<T::AccountId>>;
 
+        /// Index of file hashes, the first file and version registering the hash
+        FileByHash
+            get(fn file_by_hash):
+            map hasher(blake2_128_concat) H256 => Option<(FileId, u32)>;
+
+        /// Proof-of-existence anchors of single hashes and Merkle roots
+        Anchors
+            get(fn anchors):
+            map hasher(blake2_128_concat) H256 => Option<AnchorStruct<T::AccountId, T::BlockNumber>>;
+
+        /// Signing delegations: principal, (delegate, file or None for all files) => expiry block
+        Delegations
+            get(fn delegations):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) (T::AccountId, Option<FileId>)
+            => Option<T::BlockNumber>;
+
+        /// Block in which a file was finalized, finalized files are immutable
+        FinalizedAt
+            get(fn finalized_at):
+            map hasher(blake2_128_concat) FileId => Option<T::BlockNumber>;
+
+        /// File superseding the given file
+        SupersededBy
+            get(fn superseded_by):
+            map hasher(blake2_128_concat) FileId => Option<FileId>;
+
+        /// File superseded by the given file
+        Supersedes
+            get(fn supersedes):
+            map hasher(blake2_128_concat) FileId => Option<FileId>;
+
+        /// Named signer groups
+        GroupByID
+            get(fn group_by_id):
+            map hasher(blake2_128_concat) GroupId => Option<GroupStruct<T::AccountId>>;
+
+        /// Next signer group id
+        NextGroupId: GroupId;
+
+        /// Document templates with signer roles
+        TemplateByID
+            get(fn template_by_id):
+            map hasher(blake2_128_concat) TemplateId => Option<TemplateStruct<T::AccountId>>;
+
+        /// Next template id
+        NextTemplateId: TemplateId;
+
+        /// Template role of a signer of a file created from a template
+        SignerRole
+            get(fn signer_role):
+            double_map hasher(blake2_128_concat) FileId, hasher(blake2_128_concat) T::AccountId => Option<Vec<u8>>;
+
+        /// Delegations by expiry block, for expiry notifications
+        DelegationExpiries
+            get(fn delegation_expiries):
+            map hasher(twox_64_concat) T::BlockNumber => Vec<(T::AccountId, T::AccountId, Option<FileId>)>;
+
+        /// Number of notification batches indexed off chain in the current block, cleared in on_finalize
+        NotificationBatches: u32;
+
+        /// Details of private files encrypted to the recipient account
+        Envelopes
+            get(fn envelope):
+            double_map hasher(blake2_128_concat) FileId, hasher(blake2_128_concat) T::AccountId => Option<Vec<u8>>;
+
+        /// Block in which a mismatch of the document behind a version location was reported
+        Mismatches
+            get(fn mismatches):
+            double_map hasher(blake2_128_concat) FileId, hasher(twox_64_concat) u32 => Option<T::BlockNumber>;
+
+        /// Block in which a mismatch of the version was last cleared, earlier reports can't be replayed
+        MismatchClearedAt
+            get(fn mismatch_cleared_at):
+            double_map hasher(blake2_128_concat) FileId, hasher(twox_64_concat) u32 => Option<T::BlockNumber>;
+
+        /// Commitments blake2_256(secret) to IDs of files created later with FileIdMode::Committed,
+        /// account, commitment => (block of the commitment, reserved file ID)
+        IdCommitments
+            get(fn id_commitment):
+            double_map hasher(blake2_128_concat) T::AccountId, hasher(identity) H256 => Option<(T::BlockNumber, FileId)>;
+
+        /// File IDs reserved by pending commitments, only the committing account can create a file with them
+        ReservedIds
+            get(fn reserved_id):
+            map hasher(blake2_128_concat) FileId => Option<T::AccountId>;
+
+        /// Number of versions queued for a hash check by the off-chain worker, the queue itself is in the off-chain index
+        HashCheckCount
+            get(fn hash_check_count):
+            u64;
+
+        /// Accounts of the keys allowed to sign mismatch reports
+        HashCheckers
+            get(fn hash_checkers)
+            config():
+            Vec<T::AccountId>;
+
+        /// Per-signer nonce of pre-signed payloads, protects against replay
+        PayloadNonce
+            get(fn payload_nonce):
+            map hasher(blake2_128_concat) T::AccountId => u64;
+
         /// Nonce for random file id generating 
         NonceId: u64;
     }
+    add_extra_genesis {
+        /// Pre-registered files, see GenesisFile
+        config(files): Vec<GenesisFile<T::AccountId>>;
+        build(|config: &GenesisConfig<T>| {
+            for (id, owner, tag, filehash, signers, signed) in config.files.iter() {
+                assert!(!tag.is_empty(), "genesis file has an empty tag");
+                assert!(!FileByID::<T>::contains_key(id), "genesis file id is duplicated");
+
+                let filehash = H256::from(*filehash);
+                let mut file = FileStruct::new(owner.clone(), *id, tag.clone(), &filehash, None);
+                assert!(signers.len() <= T::MaxSigners::get() as usize, "genesis file has more than MaxSigners signers");
+                for signer in signers.iter() {
+                    file.assign_signer_to_file(signer.clone()).expect("genesis signer is duplicated");
+                }
+                for signer in signed.iter() {
+                    assert!(file.signers.contains(signer), "genesis signature of an address that is not a signer");
+                    file.sign_latest_version(signer.clone(), None).expect("genesis signature is duplicated");
+                }
+
+                if !FileByHash::contains_key(filehash) {
+                    FileByHash::insert(filehash, (*id, 0));
+                }
+                FileByID::<T>::insert(id, file);
+            }
+        });
+    }
 }
 
 decl_event! (
     pub enum Event<T>
     where 
         AccountId = <T as frame_system::Config>::AccountId,
+        BlockNumber = <T as frame_system::Config>::BlockNumber,
     {
+        /// \[account, fileid, version, filehash, tag\]
+        VersionCreated(AccountId, FileId, u32, H256, Vec<u8>),
+        /// \[oldfileid, newfileid\]
+        Superseded(FileId, FileId),
+        /// \[account, fileid, block\]
+        FileFinalized(AccountId, FileId, BlockNumber),
+        /// \[fileid, oldowner, newowner\]
+        OwnerForceTransferred(FileId, AccountId, AccountId),
+        /// \[fileid, signer\]
+        SignerForceRemoved(FileId, AccountId),
+        /// \[fileid\]
+        FileForceDeleted(FileId),
         /// \[account, fileid, signer\]
+        SignerInvited(AccountId, FileId, AccountId),
+        /// \[owner, fileid, signer\], the invited signer has accepted
         SignerAssigned(AccountId, FileId, AccountId),
-        /// \[account, fileid\]
-        FileCreated(AccountId, FileId),
+        /// \[signer, fileid\]
+        InvitationAccepted(AccountId, FileId),
+        /// \[signer, fileid\]
+        InvitationDeclined(AccountId, FileId),
+        /// \[account, fileid, filehash, tag\]
+        FileCreated(AccountId, FileId, H256, Vec<u8>),
+        /// \[account, commitment, reserved fileid\]
+        FileIdCommitted(AccountId, H256, FileId),
         /// \[account, fileid, signer\]
         SignerDeleted(AccountId, FileId, AccountId),
-        /// \[account, fileid\]
-        FileSigned(AccountId, FileId),
+        /// \[account, fileid, version, filehash, tag\]
+        FileSigned(AccountId, FileId, u32, H256, Vec<u8>),
+        /// \[fileid, version, filehash\]
+        SignatureCompleted(FileId, u32, H256),
+        /// \[delegate, principal, fileid, version, filehash, tag\]
+        FileSignedByDelegate(AccountId, AccountId, FileId, u32, H256, Vec<u8>),
+        /// \[principal, delegate, fileid, expiry\]
+        DelegateAdded(AccountId, AccountId, Option<FileId>, BlockNumber),
+        /// \[principal, delegate, fileid\]
+        DelegateRemoved(AccountId, AccountId, Option<FileId>),
+        /// \[account, templateid\]
+        TemplateCreated(AccountId, TemplateId),
+        /// \[account, templateid, fileid\]
+        TemplateInstantiated(AccountId, TemplateId, FileId),
+        /// \[account, groupid\]
+        GroupCreated(AccountId, GroupId),
+        /// \[account, groupid\]
+        GroupUpdated(AccountId, GroupId),
+        /// \[account, fileid, groupid\]
+        GroupAssigned(AccountId, FileId, GroupId),
+        /// \[account, fileid, groupid\]
+        GroupDeleted(AccountId, FileId, GroupId),
+        /// \[account, fileid, version, location\]
+        LocationSet(AccountId, FileId, u32, FileLocation),
+        /// \[account, fileid, key, value\]
+        AttributeSet(AccountId, FileId, Vec<u8>, Vec<u8>),
+        /// \[account, fileid, key\]
+        AttributeCleared(AccountId, FileId, Vec<u8>),
+        /// \[account, hash\]
+        HashAnchored(AccountId, H256),
+        /// \[account, root, leaves\]
+        BatchAnchored(AccountId, H256, u32),
+        /// \[checker, fileid, version\]
+        MismatchReported(AccountId, FileId, u32),
+        /// \[fileid, version\]
+        MismatchCleared(FileId, u32),
+        /// \[checkers\]
+        HashCheckersSet(Vec<AccountId>),
+        /// \[account, fileid, recipient\]
+        RevealedTo(AccountId, FileId, AccountId),
+        /// \[account, fileid, recipient\]
+        RevealRevoked(AccountId, FileId, AccountId),
+        /// \[account, fileid, version, salt\]
+        SaltRevealed(AccountId, FileId, u32, Vec<u8>),
     }
 );
 
@@ -70,6 +432,32 @@ decl_error! {
         AddressNotSigner,
         /// Address is not an owner of a file
         AddressNotOwner,
+        /// No such version in file
+        VersionNotFound,
+        /// File is already superseded or already supersedes another file
+        AlreadySuperseded,
+        /// Validation error - file can't supersede itself
+        SelfSupersession,
+        /// File is finalized and can't be changed
+        FileFinalized,
+        /// Latest version of a file is not signed by all signers
+        VersionNotApproved,
+        /// Address has already signed the latest version of a file
+        AlreadySigned,
+        /// Signer has signed a version and can't be removed under the current policy
+        SignerHasSigned,
+        /// Address is already a signer of a file or is already invited
+        AlreadyAssigned,
+        /// File already has MaxSigners signers, invitations and group members
+        TooManySigners,
+        /// Entries given to force_delete_file are fewer than the storage entries of the file
+        EntriesUnderestimated,
+        /// Signer group is already assigned to a file
+        GroupAlreadyAssigned,
+        /// File has no versions
+        NoVersions,
+        /// Address has no pending invitation to a file
+        NoInvitation,
         /// No such file in storage
         FileNotFound,
         /// Validation error - no tag
@@ -78,6 +466,115 @@ decl_error! {
         FileHasNoSigners,
         /// File id is busy
         IdAlreadyExists,
+        /// File hash is already registered
+        HashAlreadyRegistered,
+        /// Validation error - salt is longer than MAX_ID_SALT_LENGTH
+        SaltTooLong,
+        /// File ID commitment already exists
+        IdCommitmentExists,
+        /// No file ID commitment of the caller for the revealed secret
+        IdCommitmentNotFound,
+        /// File ID commitment must be revealed in a later block
+        IdRevealTooEarly,
+        /// Validation error - file ID secret is empty or longer than MAX_ID_SECRET_LENGTH
+        InvalidIdSecret,
+        /// File ID is reserved by a pending commitment
+        IdReserved,
+        /// Validation error - location pointer is longer than MAX_LOCATION_LENGTH
+        LocationTooLong,
+        /// Validation error - malformed location pointer or IPFS CID
+        InvalidLocation,
+        /// Validation error - no attribute key
+        EmptyAttributeKey,
+        /// Validation error - attribute key is longer than MAX_ATTRIBUTE_KEY_LENGTH
+        AttributeKeyTooLong,
+        /// Validation error - attribute value is longer than MAX_ATTRIBUTE_VALUE_LENGTH
+        AttributeValueTooLong,
+        /// File already has MAX_ATTRIBUTES attributes
+        TooManyAttributes,
+        /// No such attribute in file
+        AttributeNotFound,
+        /// No such signer group in storage
+        GroupNotFound,
+        /// Address is not an owner of a signer group
+        AddressNotGroupOwner,
+        /// Signer group is not assigned to a file
+        GroupNotAssigned,
+        /// File already has MAX_FILE_GROUPS signer groups
+        TooManyGroups,
+        /// Validation error - no group name or name is longer than MAX_GROUP_NAME_LENGTH
+        InvalidGroupName,
+        /// Validation error - no group members or more than MAX_GROUP_MEMBERS
+        InvalidGroupMembers,
+        /// Validation error - threshold is zero or exceeds the number of members or roles
+        InvalidThreshold,
+        /// No such template in storage
+        TemplateNotFound,
+        /// Validation error - no template name or name is longer than MAX_TEMPLATE_NAME_LENGTH
+        InvalidTemplateName,
+        /// Validation error - no roles, too many roles, empty, too long or duplicate role names
+        InvalidRoles,
+        /// Given accounts don't fill every template role exactly once
+        RoleMismatch,
+        /// Payload signature is invalid, stale or not for the latest version
+        InvalidPayloadSignature,
+        /// Address has no valid delegation from the principal
+        NotDelegate,
+        /// No such delegation in storage
+        DelegationNotFound,
+        /// Validation error - delegation expiry is not in the future
+        InvalidExpiry,
+        /// MAX_EXPIRIES_PER_BLOCK delegations already expire in the block
+        TooManyExpiries,
+        /// Validation error - account can't delegate to itself
+        SelfDelegation,
+        /// Hash is already anchored
+        AlreadyAnchored,
+        /// Validation error - batch has no leaves
+        EmptyBatch,
+        /// Version has no location to check
+        NoLocation,
+        /// Mismatch of the version is already reported
+        MismatchAlreadyReported,
+        /// No mismatch of the version is reported
+        MismatchNotFound,
+        /// Report is not signed by a registered hash checker
+        NotHashChecker,
+        /// Report was signed before the mismatch was last cleared or more than MISMATCH_REPORT_LONGEVITY blocks ago
+        MismatchReportOutdated,
+        /// Validation error - more than MAX_HASH_CHECKERS hash checkers
+        TooManyHashCheckers,
+        /// File is not private
+        NotPrivateFile,
+        /// Validation error - tag of a private file is not a 32 byte commitment
+        InvalidCommitment,
+        /// Validation error - private files take no plaintext attributes or locations, they go into envelopes
+        PrivateFileMetadata,
+        /// Validation error - envelope is empty or longer than MAX_ENVELOPE_LENGTH
+        InvalidEnvelope,
+        /// Envelope for the recipient does not exist
+        EnvelopeNotFound,
+        /// Filehash of the version is not salted
+        NotSalted,
+        /// Validation error - salt is empty or longer than MAX_HASH_SALT_LENGTH
+        InvalidHashSalt,
+    }
+}
+
+impl<T: Config> From<FileError> for Error<T> {
+    fn from(error: FileError) -> Self {
+        match error {
+            FileError::NoVersions => Error::<T>::NoVersions,
+            FileError::AlreadySigned => Error::<T>::AlreadySigned,
+            FileError::AlreadyAssigned => Error::<T>::AlreadyAssigned,
+            FileError::AddressNotSigner => Error::<T>::AddressNotSigner,
+            FileError::NoInvitation => Error::<T>::NoInvitation,
+            FileError::GroupAlreadyAssigned => Error::<T>::GroupAlreadyAssigned,
+            FileError::GroupNotAssigned => Error::<T>::GroupNotAssigned,
+            FileError::TooManyGroups => Error::<T>::TooManyGroups,
+            FileError::TooManyAttributes => Error::<T>::TooManyAttributes,
+            FileError::AttributeNotFound => Error::<T>::AttributeNotFound,
+        }
     }
 }
 
@@ -87,66 +584,327 @@ decl_module! {
         fn deposit_event() = default;
         type Error = Error<T>;
 
-        #[weight = T::DbWeight::get().reads_writes(2, 1) + 10_000]
-        pub fn create_new_file(origin, tag: Vec<u8>, filehash: H256, file_id_option: Option<FileId>) -> DispatchResult {
+        // the expiries of a block are bounded by MAX_EXPIRIES_PER_BLOCK, the weight includes the write of on_finalize
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            if !DelegationExpiries::<T>::contains_key(now) {
+                return T::DbWeight::get().reads_writes(1, 1);
+            }
+            let expiries = DelegationExpiries::<T>::take(now);
+            let count = expiries.len() as u64;
+            let mut notifications = Vec::new();
+            for (principal, delegate, file) in expiries {
+                // delegation could be removed or renewed meanwhile
+                if Delegations::<T>::get(&principal, (delegate.clone(), file)) == Some(now) {
+                    notifications.push(Notification::DelegationExpired(principal, delegate, file, now));
+                }
+            }
+            Self::index_notifications(notifications);
+            T::DbWeight::get().reads_writes(3 + count, 3)
+        }
+
+        fn on_finalize(_now: T::BlockNumber) {
+            NotificationBatches::kill();
+        }
+
+        fn offchain_worker(now: T::BlockNumber) {
+            Self::send_notifications(now);
+            let interval = T::HashCheckInterval::get();
+            if !interval.is_zero() && (now % interval).is_zero() {
+                Self::check_remote_hashes(now);
+            }
+        }
+
+        #[weight = T::WeightInfo::create_new_file()]
+        pub fn create_new_file(origin, tag: Vec<u8>, filehash: H256, location: Option<FileLocation>, id_mode: FileIdMode, salted: bool) -> DispatchResult {
             ensure!(!tag.is_empty(), Error::<T>::EmptyTag);
             let caller = ensure_signed(origin)?;
-            
-            // Update last created file ID
-            let file_id = match file_id_option {
-                Some(id) => id,
-                None => Self::get_random_id()
-            };
-            ensure!(<FileByID<T>>::get(file_id).is_none(), Error::<T>::IdAlreadyExists);
-            let new_file = FileStruct::<<T as frame_system::Config>::AccountId>::new(caller.clone(), file_id, tag, &filehash);
-            <FileByID<T>>::insert(file_id, new_file);
-            Self::deposit_event(RawEvent::FileCreated(caller, file_id));
+            Self::create_file(caller, tag, filehash, location, id_mode, SigningPolicy::All, false, salted)?;
             Ok(())
         }
 
-        #[weight = T::DbWeight::get().reads_writes(1, 1) + 10_000]
-		pub fn sign_latest_version(origin, id: FileId) {
-			let caller = ensure_signed(origin)?;
-            FileByID::<T>::try_mutate(
-                id, |file_option| -> DispatchResult {
+        /// Commits blake2_256(secret) of a file ID secret revealed with FileIdMode::Committed in a later block,
+        /// reserves the file ID so it can't be squatted in front of the reveal
+        #[weight = T::WeightInfo::commit_file_id()]
+        pub fn commit_file_id(origin, commitment: H256) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!IdCommitments::<T>::contains_key(&caller, commitment), Error::<T>::IdCommitmentExists);
+            let id = Self::get_committed_id(&caller, &commitment)?;
+
+            IdCommitments::<T>::insert(&caller, commitment, (<frame_system::Module<T>>::block_number(), id));
+            ReservedIds::<T>::insert(id, &caller);
+            Self::deposit_event(RawEvent::FileIdCommitted(caller, commitment, id));
+        }
+
+        /// Creates a file which tag is only the commitment blake2_256(tag ++ salt), see tag_commitment
+        #[weight = T::WeightInfo::create_private_file()]
+        pub fn create_private_file(origin, commitment: H256, filehash: H256, id_mode: FileIdMode, salted: bool) {
+            let caller = ensure_signed(origin)?;
+            Self::create_file(caller, commitment.as_bytes().to_vec(), filehash, None, id_mode, SigningPolicy::All, true, salted)?;
+        }
+
+        /// Discloses details of a private file to the recipient, the envelope is encrypted off chain to the recipient key
+        #[weight = T::WeightInfo::reveal_to(T::MaxSigners::get())]
+        pub fn reveal_to(origin, id: FileId, recipient: T::AccountId, envelope: Vec<u8>) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!envelope.is_empty() && envelope.len() <= MAX_ENVELOPE_LENGTH, Error::<T>::InvalidEnvelope);
+            let file = FileByID::<T>::get(id).ok_or(Error::<T>::FileNotFound)?;
+            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+            ensure!(file.private, Error::<T>::NotPrivateFile);
+
+            Envelopes::<T>::insert(id, &recipient, envelope);
+            Self::deposit_event(RawEvent::RevealedTo(caller, id, recipient));
+        }
+
+        /// Discloses the salt of a salted filehash, the document itself stays off chain. A wrong salt is
+        /// corrected by revealing it again until the file is finalized
+        #[weight = T::WeightInfo::reveal_salt(T::MaxSigners::get())]
+        pub fn reveal_salt(origin, id: FileId, version: u32, salt: Vec<u8>) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!salt.is_empty() && salt.len() <= MAX_HASH_SALT_LENGTH, Error::<T>::InvalidHashSalt);
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+
+            let located = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<bool, DispatchError> {
                     match file_option {
-                        None => return Err(Error::<T>::FileNotFound.into()),
+                        None => Err(Error::<T>::FileNotFound.into()),
                         Some(file) => {
-                            ensure!(file.signers.iter().any(|x| *x == caller), Error::<T>::AddressNotSigner);
-                            file.sign_latest_version(caller.clone());
+                            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+                            let vers = file.versions.get_mut(version as usize).ok_or(Error::<T>::VersionNotFound)?;
+                            ensure!(vers.salt != SaltState::Unsalted, Error::<T>::NotSalted);
+                            vers.salt = SaltState::Revealed(salt.clone());
+                            Ok(vers.location.is_some())
                         }
                     }
-                    Ok(())
-                })?;
+                }
+            )?;
+            // a mismatch found with the previous salt is void, the version is checked again with the new one
+            if Mismatches::<T>::contains_key(id, version) {
+                Self::reset_mismatch(id, version);
+                Self::deposit_event(RawEvent::MismatchCleared(id, version));
+            }
+            if located {
+                Self::queue_hash_check(id, version);
+            }
+
+            Self::deposit_event(RawEvent::SaltRevealed(caller, id, version, salt));
+        }
+
+        /// Removes the envelope of the recipient, details already read can't be taken back
+        #[weight = T::WeightInfo::revoke_reveal(T::MaxSigners::get())]
+        pub fn revoke_reveal(origin, id: FileId, recipient: T::AccountId) {
+            let caller = ensure_signed(origin)?;
+            let file = FileByID::<T>::get(id).ok_or(Error::<T>::FileNotFound)?;
+            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+            ensure!(Envelopes::<T>::contains_key(id, &recipient), Error::<T>::EnvelopeNotFound);
+
+            Envelopes::<T>::remove(id, &recipient);
+            Self::deposit_event(RawEvent::RevealRevoked(caller, id, recipient));
+        }
+
+        #[weight = T::WeightInfo::create_template(roles.len() as u32)]
+        pub fn create_template(origin, name: Vec<u8>, roles: Vec<Vec<u8>>, policy: SigningPolicy) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!name.is_empty() && name.len() <= MAX_TEMPLATE_NAME_LENGTH, Error::<T>::InvalidTemplateName);
+            ensure!(!roles.is_empty() && roles.len() <= MAX_TEMPLATE_ROLES, Error::<T>::InvalidRoles);
+            ensure!(roles.iter().all(|role| !role.is_empty() && role.len() <= MAX_ROLE_LENGTH), Error::<T>::InvalidRoles);
+            ensure!(roles.iter().enumerate().all(|(i, role)| !roles[..i].contains(role)), Error::<T>::InvalidRoles);
+            if let SigningPolicy::Threshold(threshold) = policy {
+                ensure!(threshold > 0 && threshold as usize <= roles.len(), Error::<T>::InvalidThreshold);
+            }
+
+            let template_id = NextTemplateId::get();
+            NextTemplateId::put(template_id.wrapping_add(1));
+            <TemplateByID<T>>::insert(template_id, TemplateStruct { owner: caller.clone(), name, roles, policy });
+            Self::deposit_event(RawEvent::TemplateCreated(caller, template_id));
+        }
+
+        #[weight = T::WeightInfo::create_from_template(roles.len() as u32)]
+        pub fn create_from_template(origin, template_id: TemplateId, filehash: H256, id_mode: FileIdMode, roles: Vec<(Vec<u8>, T::AccountId)>, salted: bool) {
+            let caller = ensure_signed(origin)?;
+            let template = match TemplateByID::<T>::get(template_id) {
+                None => return Err(Error::<T>::TemplateNotFound.into()),
+                Some(template) => template
+            };
+            // every role slot is filled exactly once and by distinct accounts
+            ensure!(roles.len() == template.roles.len()
+                && template.roles.iter().all(|role| roles.iter().filter(|(r, _)| r == role).count() == 1)
+                && roles.iter().enumerate().all(|(i, (_, signer))| !roles[..i].iter().any(|(_, s)| s == signer)),
+                Error::<T>::RoleMismatch);
+            ensure!(roles.len() <= T::MaxSigners::get() as usize, Error::<T>::TooManySigners);
+
+            let file_id = Self::create_file(caller.clone(), template.name, filehash, None, id_mode, template.policy, false, salted)?;
+            FileByID::<T>::try_mutate(file_id, |file_option| -> Result<(), Error<T>> {
+                match file_option {
+                    None => Err(Error::<T>::FileNotFound),
+                    Some(file) => {
+                        for (_, signer) in roles.iter() {
+                            file.invite_signer_to_file(signer.clone())?;
+                        }
+                        Ok(())
+                    }
+                }
+            })?;
+            let mut notifications = Vec::with_capacity(roles.len());
+            for (role, signer) in roles {
+                notifications.push(Notification::SignerInvited(file_id, signer.clone()));
+                Self::deposit_event(RawEvent::SignerInvited(caller.clone(), file_id, signer.clone()));
+                SignerRole::<T>::insert(file_id, signer, role);
+            }
+            Self::index_notifications(notifications);
+            Self::deposit_event(RawEvent::TemplateInstantiated(caller, template_id, file_id));
+        }
+
+        #[weight = T::WeightInfo::create_new_version(T::MaxSigners::get())]
+        pub fn create_new_version(origin, id: FileId, tag: Vec<u8>, filehash: H256, location: Option<FileLocation>, parent: Option<u32>, salted: bool) {
+            ensure!(!tag.is_empty(), Error::<T>::EmptyTag);
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+            if let Some(loc) = &location {
+                Self::validate_location(loc)?;
+            }
+            Self::check_hash_policy(&caller, &filehash)?;
+
+            let version = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<u32, Error<T>> {
+                    match file_option {
+                        None => Err(Error::<T>::FileNotFound),
+                        Some(file) => {
+                            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+                            ensure!(!file.private || tag.len() == 32, Error::<T>::InvalidCommitment);
+                            ensure!(!file.private || location.is_none(), Error::<T>::PrivateFileMetadata);
+                            // by default a new version follows the latest one
+                            let latest = (file.versions.len() - 1) as u32;
+                            let parent = parent.unwrap_or(latest);
+                            ensure!(parent <= latest, Error::<T>::VersionNotFound);
+                            let version = file.add_version(tag.clone(), &filehash, location.clone(), parent);
+                            if salted {
+                                file.versions[version as usize].salt = SaltState::Committed;
+                            }
+                            Ok(version)
+                        }
+                    }
+                }
+            )?;
+            if !FileByHash::contains_key(filehash) {
+                FileByHash::insert(filehash, (id, version));
+            }
+
+            Self::deposit_event(RawEvent::VersionCreated(caller.clone(), id, version, filehash, tag));
+            if let Some(loc) = location {
+                // salted version is queued once the salt is revealed
+                if !salted {
+                    Self::queue_hash_check(id, version);
+                }
+                Self::deposit_event(RawEvent::LocationSet(caller, id, version, loc));
+            }
+        }
 
-            Self::deposit_event(RawEvent::FileSigned(caller, id));
+        #[weight = T::WeightInfo::supersede_file(T::MaxSigners::get())]
+        pub fn supersede_file(origin, old_id: FileId, new_id: FileId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(old_id != new_id, Error::<T>::SelfSupersession);
+            ensure!(FileByID::<T>::contains_key(old_id) && FileByID::<T>::contains_key(new_id), Error::<T>::FileNotFound);
+            ensure!(Self::address_is_owner_for_file(old_id, &caller) && Self::address_is_owner_for_file(new_id, &caller),
+                Error::<T>::AddressNotOwner);
+            // the new file must be the head of no lineage yet, which also rules out cycles
+            ensure!(!SupersededBy::contains_key(old_id) && !SupersededBy::contains_key(new_id)
+                && !Supersedes::contains_key(new_id), Error::<T>::AlreadySuperseded);
+
+            SupersededBy::insert(old_id, new_id);
+            Supersedes::insert(new_id, old_id);
+            Self::deposit_event(RawEvent::Superseded(old_id, new_id));
+        }
+
+        #[weight = T::WeightInfo::finalize_file(T::MaxSigners::get())]
+        pub fn finalize_file(origin, id: FileId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+            ensure!(Self::address_is_owner_for_file(id, &caller), Error::<T>::AddressNotOwner);
+            ensure!(Self::latest_version_is_signed(id), Error::<T>::VersionNotApproved);
+
+            let block = <frame_system::Module<T>>::block_number();
+            FinalizedAt::<T>::insert(id, block);
+            Self::deposit_event(RawEvent::FileFinalized(caller, id, block));
+        }
+
+        #[weight = T::WeightInfo::sign_latest_version(T::MaxSigners::get())]
+		pub fn sign_latest_version(origin, id: FileId) {
+			let caller = ensure_signed(origin)?;
+            Self::sign_file(id, &caller, None)?;
 		}
-        
-        #[weight = T::DbWeight::get().reads_writes(1, 1) + 10_000]
+
+        #[weight = T::WeightInfo::sign_latest_version_on_behalf(T::MaxSigners::get())]
+        pub fn sign_latest_version_on_behalf(origin, id: FileId, principal: T::AccountId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(Self::address_is_delegate_for_file(id, &principal, &caller), Error::<T>::NotDelegate);
+            Self::sign_file(id, &principal, Some(caller))?;
+        }
+
+        /// Unsigned call, submitted by a relayer with a payload pre-signed by the signer, see signing_payload
+        #[weight = T::WeightInfo::sign_with_payload(T::MaxSigners::get())]
+        pub fn sign_with_payload(origin, id: FileId, version: u32, signer: T::AccountId, signature: T::PayloadSignature) {
+            ensure_none(origin)?;
+            ensure!(Self::payload_is_valid(id, version, &signer, &signature), Error::<T>::InvalidPayloadSignature);
+            Self::sign_file(id, &signer, None)?;
+            PayloadNonce::<T>::mutate(&signer, |nonce| *nonce = nonce.wrapping_add(1));
+        }
+
+        #[weight = T::WeightInfo::add_delegate()]
+        pub fn add_delegate(origin, delegate: T::AccountId, file: Option<FileId>, expiry: T::BlockNumber) {
+            let caller = ensure_signed(origin)?;
+            ensure!(caller != delegate, Error::<T>::SelfDelegation);
+            ensure!(expiry > <frame_system::Module<T>>::block_number(), Error::<T>::InvalidExpiry);
+
+            // renewal with the same expiry keeps the single expiry entry
+            if Delegations::<T>::get(&caller, (delegate.clone(), file)) != Some(expiry) {
+                let expiries = DelegationExpiries::<T>::decode_len(expiry).unwrap_or(0);
+                ensure!(expiries < MAX_EXPIRIES_PER_BLOCK, Error::<T>::TooManyExpiries);
+                DelegationExpiries::<T>::append(expiry, (caller.clone(), delegate.clone(), file));
+            }
+            Delegations::<T>::insert(&caller, (delegate.clone(), file), expiry);
+            Self::deposit_event(RawEvent::DelegateAdded(caller, delegate, file, expiry));
+        }
+
+        #[weight = T::WeightInfo::remove_delegate()]
+        pub fn remove_delegate(origin, delegate: T::AccountId, file: Option<FileId>) {
+            let caller = ensure_signed(origin)?;
+            let key = (delegate.clone(), file);
+            ensure!(Delegations::<T>::contains_key(&caller, &key), Error::<T>::DelegationNotFound);
+
+            Delegations::<T>::remove(&caller, &key);
+            Self::deposit_event(RawEvent::DelegateRemoved(caller, delegate, file));
+        }
+
+        #[weight = T::WeightInfo::delete_signer(T::MaxSigners::get())]
         pub fn delete_signer(origin, id: FileId, signer: T::AccountId)  {
             let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
 
-            FileByID::<T>::try_mutate(
-                id, |file_option| -> DispatchResult {
+            let approved = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<Option<(u32, H256)>, DispatchError> {
                     match file_option {
-                        None => return Err(Error::<T>::FileNotFound.into()),
+                        None => Err(Error::<T>::FileNotFound.into()),
                         Some(file) => {
                             ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
-                            ensure!(file.signers.iter().any(|x| *x == signer), Error::<T>::AddressNotSigner);
-                            ensure!(file.delete_signer_from_file(signer.clone()).is_ok(), 
-                                   Error::<T>::AddressNotSigner);
+                            let was_approved = Self::version_is_approved(file);
+                            // pending invitation is cancelled the same way
+                            if file.decline_invitation(&signer).is_err() {
+                                Self::remove_signer(file, &signer, T::SignerRemovalPolicy::get())?;
+                            }
+                            Ok(Self::newly_approved(file, was_approved))
                         }
                     }
-                    Ok(())
                 }
             )?;
 
             Self::deposit_event(RawEvent::SignerDeleted(caller, id, signer));
+            Self::note_approval(id, approved);
         }
 
-        #[weight = T::DbWeight::get().reads_writes(1, 1) + 10_000]
+        #[weight = T::WeightInfo::assign_signer(T::MaxSigners::get())]
         pub fn assign_signer(origin, id: FileId, signer: T::AccountId) {
             let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
 
             FileByID::<T>::try_mutate(
                 id, |file_option| -> DispatchResult {
@@ -154,83 +912,1146 @@ decl_module! {
                         None => return Err(Error::<T>::FileNotFound.into()),
                         Some(file) => {
                             ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
-                            file.assign_signer_to_file(signer.clone());
+                            Self::check_signer_capacity(file, 1)?;
+                            file.invite_signer_to_file(signer.clone()).map_err(Error::<T>::from)?;
                         }
                     }
                     Ok(())
                 }
             )?;
 
-            Self::deposit_event(RawEvent::SignerAssigned(caller, id, signer));
+            Self::index_notifications(vec![Notification::SignerInvited(id, signer.clone())]);
+            Self::deposit_event(RawEvent::SignerInvited(caller, id, signer));
         }
-    }
-}
 
-impl<T: Config> Module<T> {
-    /// <pre>
-    /// Method: address_is_auditor_for_file(id: u32, address: &T::AccountId) -> bool
-    /// Arguments: id: FileId, address: &T::AccountId - file ID, address
-    ///
-    /// Checks if the address is an auditor for the given file
-    /// </pre>
-    pub fn address_is_signer_for_file(id: FileId, address: &T::AccountId) -> bool {
-        match FileByID::<T>::get(id) {
-            None => false,
-            Some(file) => file.signers.iter().any(|x| x == address)
+        #[weight = T::WeightInfo::accept_invitation(T::MaxSigners::get())]
+        pub fn accept_invitation(origin, id: FileId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+
+            let owner = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<T::AccountId, DispatchError> {
+                    match file_option {
+                        None => Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            // accepts the signer invitation and invitations through signer groups
+                            let accepted_groups = file.accept_group_invitations(&caller);
+                            if let Err(error) = file.accept_invitation(caller.clone()) {
+                                ensure!(accepted_groups, Error::<T>::from(error));
+                            }
+                            Ok(file.owner.clone())
+                        }
+                    }
+                }
+            )?;
+
+            Self::deposit_event(RawEvent::InvitationAccepted(caller.clone(), id));
+            // the signer counts from now on, same event as a direct assignment before invitations
+            Self::deposit_event(RawEvent::SignerAssigned(owner, id, caller));
         }
-    }
 
-    /// <pre>
-    /// Method: address_has_signed_the_file(id: u32, address: &T::AccountId) -> bool
-    /// Arguments: id: FileId, address: &T::AccountId - file ID, address
-    ///
-    /// Checks if the address has signed last version of the given file
-    /// </pre>
-    pub fn address_has_signed_the_file(id: FileId, address: &T::AccountId) -> bool {
-        match FileByID::<T>::get(id) {
-            None => false,
-            Some(file) => {
-                if let Some(vers_strunct) = file.versions.last() {
-                    return vers_strunct.signatures.iter().any(|sign| sign.address == *address && sign.signed);
+        #[weight = T::WeightInfo::decline_invitation(T::MaxSigners::get())]
+        pub fn decline_invitation(origin, id: FileId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+
+            FileByID::<T>::try_mutate(
+                id, |file_option| -> DispatchResult {
+                    match file_option {
+                        None => return Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            let declined_groups = file.decline_group_invitations(&caller);
+                            if let Err(error) = file.decline_invitation(&caller) {
+                                ensure!(declined_groups, Error::<T>::from(error));
+                            }
+                        }
+                    }
+                    Ok(())
                 }
-                false
-            }
+            )?;
+
+            Self::deposit_event(RawEvent::InvitationDeclined(caller, id));
         }
-    }
 
-    /// <pre>
-    /// Method: address_is_owner_for_file(id: u32, address: &T::AccountId) -> bool
-    /// Arguments: id: FileId, address: &T::AccountId - file ID, address
-    ///
-    /// Checks if the address is the owner for the given file
-    /// </pre>
-    pub fn address_is_owner_for_file(id: FileId, address: &T::AccountId) -> bool {
-        match FileByID::<T>::get(id) {
-            None => false,
-            Some(file) => file.owner == *address
+        #[weight = T::WeightInfo::create_group(members.len() as u32)]
+        pub fn create_group(origin, name: Vec<u8>, members: Vec<T::AccountId>, threshold: u32) {
+            let caller = ensure_signed(origin)?;
+            let members = Self::validate_group(&name, members, threshold)?;
+
+            let group_id = NextGroupId::get();
+            NextGroupId::put(group_id.wrapping_add(1));
+            <GroupByID<T>>::insert(group_id, GroupStruct { owner: caller.clone(), name, members, threshold });
+            Self::deposit_event(RawEvent::GroupCreated(caller, group_id));
         }
-    }
 
-    /// <pre>
-    /// Method: get_file_by_id(id: FileId) -> Option<FileStruct<<T as frame_system::Config>::AccountId>> 
-    /// Arguments: id: FileId - file ID
-    ///
-    /// Returns the file option
-    /// </pre>
-    #[inline]
-    pub fn get_file_by_id(id: FileId) -> Option<FileStruct<<T as frame_system::Config>::AccountId>> {
-        FileByID::<T>::get(id)
-    }
+        /// Updates members and threshold of a group, files the group is already assigned to keep their copy
+        #[weight = T::WeightInfo::update_group(members.len() as u32)]
+        pub fn update_group(origin, group_id: GroupId, members: Vec<T::AccountId>, threshold: u32) {
+            let caller = ensure_signed(origin)?;
 
-    fn get_random_id() -> FileId {
-        let nonce = Self::get_and_increment_nonce();
-        let rand = T::Randomness::random(&nonce);
-        codec::Encode::using_encoded(&rand, sp_io::hashing::blake2_128)
-    }
+            GroupByID::<T>::try_mutate(
+                group_id, |group_option| -> DispatchResult {
+                    match group_option {
+                        None => return Err(Error::<T>::GroupNotFound.into()),
+                        Some(group) => {
+                            ensure!(group.owner == caller, Error::<T>::AddressNotGroupOwner);
+                            group.members = Self::validate_group(&group.name, members, threshold)?;
+                            group.threshold = threshold;
+                        }
+                    }
+                    Ok(())
+                }
+            )?;
 
-    fn get_and_increment_nonce() -> Vec<u8> {
-        let nonce = NonceId::get();
-        NonceId::put(nonce.wrapping_add(1));
-        nonce.encode()
-    }
-}
\ No newline at end of file
+            Self::deposit_event(RawEvent::GroupUpdated(caller, group_id));
+        }
+
+        #[weight = T::WeightInfo::assign_group(T::MaxSigners::get())]
+        pub fn assign_group(origin, id: FileId, group_id: GroupId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+            let group = GroupByID::<T>::get(group_id).ok_or(Error::<T>::GroupNotFound)?;
+
+            // members are copied and invited, later updates of the group don't change who signs the file
+            let slot = FileGroup { group: group_id, members: Vec::new(), invited: group.members, threshold: group.threshold };
+            let invited = slot.invited.clone();
+            FileByID::<T>::try_mutate(
+                id, |file_option| -> DispatchResult {
+                    match file_option {
+                        None => return Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+                            Self::check_signer_capacity(file, slot.invited.len())?;
+                            file.assign_group_to_file(slot).map_err(Error::<T>::from)?;
+                        }
+                    }
+                    Ok(())
+                }
+            )?;
+
+            Self::index_notifications(invited.into_iter().map(|member| Notification::SignerInvited(id, member)).collect());
+            Self::deposit_event(RawEvent::GroupAssigned(caller, id, group_id));
+        }
+
+        #[weight = T::WeightInfo::delete_group(T::MaxSigners::get())]
+        pub fn delete_group(origin, id: FileId, group_id: GroupId) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+
+            let approved = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<Option<(u32, H256)>, DispatchError> {
+                    match file_option {
+                        None => Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+                            let was_approved = Self::version_is_approved(file);
+                            file.delete_group_from_file(group_id).map_err(Error::<T>::from)?;
+                            Ok(Self::newly_approved(file, was_approved))
+                        }
+                    }
+                }
+            )?;
+
+            Self::deposit_event(RawEvent::GroupDeleted(caller, id, group_id));
+            Self::note_approval(id, approved);
+        }
+
+        #[weight = T::WeightInfo::force_transfer_owner(T::MaxSigners::get())]
+        pub fn force_transfer_owner(origin, id: FileId, new_owner: T::AccountId) {
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            let old_owner = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<T::AccountId, Error<T>> {
+                    match file_option {
+                        None => Err(Error::<T>::FileNotFound),
+                        Some(file) => Ok(mem::replace(&mut file.owner, new_owner.clone()))
+                    }
+                }
+            )?;
+
+            Self::deposit_event(RawEvent::OwnerForceTransferred(id, old_owner, new_owner));
+        }
+
+        #[weight = T::WeightInfo::force_remove_signer(T::MaxSigners::get())]
+        pub fn force_remove_signer(origin, id: FileId, signer: T::AccountId) {
+            T::ForceOrigin::ensure_origin(origin)?;
+            // approvals of a finalized file are final, force_delete_file is the override then
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+
+            let approved = FileByID::<T>::try_mutate(
+                id, |file_option| -> Result<Option<(u32, H256)>, DispatchError> {
+                    match file_option {
+                        None => Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            let was_approved = Self::version_is_approved(file);
+                            if file.decline_invitation(&signer).is_err() {
+                                // governance override is never blocked, signatures are kept then
+                                let policy = match T::SignerRemovalPolicy::get() {
+                                    RemovedSignerPolicy::BlockRemoval => RemovedSignerPolicy::KeepHistorical,
+                                    policy => policy
+                                };
+                                Self::remove_signer(file, &signer, policy)?;
+                            }
+                            Ok(Self::newly_approved(file, was_approved))
+                        }
+                    }
+                }
+            )?;
+
+            Self::deposit_event(RawEvent::SignerForceRemoved(id, signer));
+            Self::note_approval(id, approved);
+        }
+
+        /// Entries is the number of storage entries of the file, see file_entries, the removal is charged for them
+        #[weight = T::WeightInfo::force_delete_file(T::MaxSigners::get(), *entries)]
+        pub fn force_delete_file(origin, id: FileId, entries: u32) {
+            T::ForceOrigin::ensure_origin(origin)?;
+            ensure!(Self::file_entries(id) <= entries, Error::<T>::EntriesUnderestimated);
+            let file = match FileByID::<T>::take(id) {
+                None => return Err(Error::<T>::FileNotFound.into()),
+                Some(file) => file
+            };
+
+            for vers in file.versions.iter() {
+                if matches!(FileByHash::get(vers.filehash), Some((file_id, _)) if file_id == id) {
+                    FileByHash::remove(vers.filehash);
+                }
+            }
+            if let Some(new_id) = SupersededBy::take(id) {
+                Supersedes::remove(new_id);
+            }
+            if let Some(old_id) = Supersedes::take(id) {
+                SupersededBy::remove(old_id);
+            }
+            FinalizedAt::<T>::remove(id);
+            SignerRole::<T>::remove_prefix(id);
+            Mismatches::<T>::remove_prefix(id);
+            MismatchClearedAt::<T>::remove_prefix(id);
+            Envelopes::<T>::remove_prefix(id);
+
+            Self::deposit_event(RawEvent::FileForceDeleted(id));
+        }
+
+        #[weight = T::WeightInfo::set_attribute(T::MaxSigners::get())]
+        pub fn set_attribute(origin, id: FileId, key: Vec<u8>, value: Vec<u8>) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+            ensure!(!key.is_empty(), Error::<T>::EmptyAttributeKey);
+            ensure!(key.len() <= MAX_ATTRIBUTE_KEY_LENGTH, Error::<T>::AttributeKeyTooLong);
+            ensure!(value.len() <= MAX_ATTRIBUTE_VALUE_LENGTH, Error::<T>::AttributeValueTooLong);
+
+            FileByID::<T>::try_mutate(
+                id, |file_option| -> DispatchResult {
+                    match file_option {
+                        None => return Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+                            ensure!(!file.private, Error::<T>::PrivateFileMetadata);
+                            file.set_attribute(key.clone(), value.clone()).map_err(Error::<T>::from)?;
+                        }
+                    }
+                    Ok(())
+                }
+            )?;
+
+            Self::deposit_event(RawEvent::AttributeSet(caller, id, key, value));
+        }
+
+        #[weight = T::WeightInfo::clear_attribute(T::MaxSigners::get())]
+        pub fn clear_attribute(origin, id: FileId, key: Vec<u8>) {
+            let caller = ensure_signed(origin)?;
+            ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+
+            FileByID::<T>::try_mutate(
+                id, |file_option| -> DispatchResult {
+                    match file_option {
+                        None => return Err(Error::<T>::FileNotFound.into()),
+                        Some(file) => {
+                            ensure!(file.owner == caller, Error::<T>::AddressNotOwner);
+                            file.clear_attribute(&key).map_err(Error::<T>::from)?;
+                        }
+                    }
+                    Ok(())
+                }
+            )?;
+
+            Self::deposit_event(RawEvent::AttributeCleared(caller, id, key));
+        }
+
+        #[weight = T::WeightInfo::anchor_hash()]
+        pub fn anchor_hash(origin, hash: H256) {
+            let caller = ensure_signed(origin)?;
+            Self::insert_anchor(caller.clone(), hash, 0)?;
+            Self::deposit_event(RawEvent::HashAnchored(caller, hash));
+        }
+
+        #[weight = T::WeightInfo::anchor_batch()]
+        pub fn anchor_batch(origin, root: H256, leaves: u32) {
+            let caller = ensure_signed(origin)?;
+            ensure!(leaves > 0, Error::<T>::EmptyBatch);
+            Self::insert_anchor(caller.clone(), root, leaves)?;
+            Self::deposit_event(RawEvent::BatchAnchored(caller, root, leaves));
+        }
+
+        /// Unsigned report of the off-chain worker, the document behind the version location doesn't match its filehash,
+        /// the payload signature is checked in validate_unsigned
+        #[weight = T::WeightInfo::report_mismatch(T::MaxSigners::get())]
+        pub fn report_mismatch(origin, payload: MismatchPayload<T::Public, T::BlockNumber>, _signature: T::Signature) {
+            ensure_none(origin)?;
+            let checker = Self::check_mismatch_report(&payload)?;
+
+            Mismatches::<T>::insert(payload.id, payload.version, <frame_system::Module<T>>::block_number());
+            Self::deposit_event(RawEvent::MismatchReported(checker, payload.id, payload.version));
+        }
+
+        /// Removes a wrong or disputed mismatch report
+        #[weight = T::WeightInfo::clear_mismatch()]
+        pub fn clear_mismatch(origin, id: FileId, version: u32) {
+            T::ForceOrigin::ensure_origin(origin)?;
+            ensure!(Mismatches::<T>::contains_key(id, version), Error::<T>::MismatchNotFound);
+
+            Self::reset_mismatch(id, version);
+            Self::deposit_event(RawEvent::MismatchCleared(id, version));
+        }
+
+        /// Replaces the accounts allowed to sign mismatch reports
+        #[weight = T::WeightInfo::set_hash_checkers(checkers.len() as u32)]
+        pub fn set_hash_checkers(origin, checkers: Vec<T::AccountId>) {
+            T::ForceOrigin::ensure_origin(origin)?;
+            let mut checkers = checkers;
+            checkers.sort();
+            checkers.dedup();
+            ensure!(checkers.len() <= MAX_HASH_CHECKERS, Error::<T>::TooManyHashCheckers);
+
+            HashCheckers::<T>::put(&checkers);
+            Self::deposit_event(RawEvent::HashCheckersSet(checkers));
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// <pre>
+    /// Method: address_is_auditor_for_file(id: u32, address: &T::AccountId) -> bool
+    /// Arguments: id: FileId, address: &T::AccountId - file ID, address
+    ///
+    /// Checks if the address is an auditor for the given file
+    /// </pre>
+    pub fn address_is_signer_for_file(id: FileId, address: &T::AccountId) -> bool {
+        match FileByID::<T>::get(id) {
+            None => false,
+            Some(file) => file.signers.iter().any(|x| x == address)
+        }
+    }
+
+    /// <pre>
+    /// Method: address_has_signed_the_file(id: u32, address: &T::AccountId) -> bool
+    /// Arguments: id: FileId, address: &T::AccountId - file ID, address
+    ///
+    /// Checks if the address has signed last version of the given file,
+    /// either itself or through a delegate. Signatures of removed signers don't count
+    /// </pre>
+    pub fn address_has_signed_the_file(id: FileId, address: &T::AccountId) -> bool {
+        match FileByID::<T>::get(id) {
+            None => false,
+            Some(file) => Self::address_can_sign(&file, address) && file.latest_version_signed_by(address)
+        }
+    }
+
+    /// <pre>
+    /// Method: address_is_delegate_for_file(id: FileId, principal: &T::AccountId, delegate: &T::AccountId) -> bool
+    /// Arguments: id, principal, delegate - file ID, signer, account signing on its behalf
+    ///
+    /// Checks if the delegate holds an unexpired delegation of the principal for the given file or for all files
+    /// </pre>
+    pub fn address_is_delegate_for_file(id: FileId, principal: &T::AccountId, delegate: &T::AccountId) -> bool {
+        let now = <frame_system::Module<T>>::block_number();
+        [Some(id), None].iter().any(|scope| {
+            match Delegations::<T>::get(principal, (delegate.clone(), *scope)) {
+                Some(expiry) => now < expiry,
+                None => false
+            }
+        })
+    }
+
+    /// <pre>
+    /// Method: latest_version_is_signed(id: FileId) -> bool
+    /// Arguments: id: FileId - file ID
+    ///
+    /// Checks if the signers of the last version of the given file satisfy the file signing policy
+    /// and every assigned signer group has reached its threshold
+    /// </pre>
+    pub fn latest_version_is_signed(id: FileId) -> bool {
+        match FileByID::<T>::get(id) {
+            None => false,
+            Some(file) => Self::version_is_approved(&file)
+        }
+    }
+
+    /// <pre>
+    /// Method: file_entries(id: FileId) -> u32
+    /// Arguments: id: FileId - file ID
+    ///
+    /// Returns the number of versions of the file plus its signer role, mismatch and envelope entries,
+    /// the entries argument of force_delete_file
+    /// </pre>
+    pub fn file_entries(id: FileId) -> u32 {
+        let versions = FileByID::<T>::get(id).map_or(0, |file| file.versions.len());
+        let entries = versions
+            + SignerRole::<T>::iter_prefix_values(id).count()
+            + Mismatches::<T>::iter_prefix_values(id).count()
+            + MismatchClearedAt::<T>::iter_prefix_values(id).count()
+            + Envelopes::<T>::iter_prefix_values(id).count();
+        entries as u32
+    }
+
+    /// <pre>
+    /// Method: file_lineage(id: FileId) -> Vec<FileId>
+    /// Arguments: id: FileId - file ID
+    ///
+    /// Returns the file and the files it supersedes, from the newest to the oldest
+    /// </pre>
+    pub fn file_lineage(id: FileId) -> Vec<FileId> {
+        let mut lineage = Vec::new();
+        let mut current = Some(id);
+        while let Some(file_id) = current {
+            lineage.push(file_id);
+            current = Supersedes::get(file_id);
+        }
+        lineage
+    }
+
+    /// <pre>
+    /// Method: version_lineage(id: FileId, version: u32) -> Vec<u32>
+    /// Arguments: id: FileId, version: u32 - file ID, version index
+    ///
+    /// Returns the version and its parent versions, from the newest to the first one
+    /// </pre>
+    pub fn version_lineage(id: FileId, version: u32) -> Vec<u32> {
+        match FileByID::<T>::get(id) {
+            None => Vec::new(),
+            Some(file) => file.version_lineage(version)
+        }
+    }
+
+    /// <pre>
+    /// Method: file_is_finalized(id: FileId) -> bool
+    /// Arguments: id: FileId - file ID
+    ///
+    /// Checks if the file is finalized and can't be changed anymore
+    /// </pre>
+    pub fn file_is_finalized(id: FileId) -> bool {
+        FinalizedAt::<T>::contains_key(id)
+    }
+
+    /// <pre>
+    /// Method: address_is_owner_for_file(id: u32, address: &T::AccountId) -> bool
+    /// Arguments: id: FileId, address: &T::AccountId - file ID, address
+    ///
+    /// Checks if the address is the owner for the given file
+    /// </pre>
+    pub fn address_is_owner_for_file(id: FileId, address: &T::AccountId) -> bool {
+        match FileByID::<T>::get(id) {
+            None => false,
+            Some(file) => file.owner == *address
+        }
+    }
+
+    /// <pre>
+    /// Method: file_attribute(id: FileId, key: &[u8]) -> Option<Vec<u8>>
+    /// Arguments: id: FileId, key: &[u8] - file ID, attribute key
+    ///
+    /// Returns the value of the given file attribute
+    /// </pre>
+    pub fn file_attribute(id: FileId, key: &[u8]) -> Option<Vec<u8>> {
+        FileByID::<T>::get(id).and_then(|file| file.attribute(key).cloned())
+    }
+
+    /// <pre>
+    /// Method: get_file_by_id(id: FileId) -> Option<FileStruct<<T as frame_system::Config>::AccountId>> 
+    /// Arguments: id: FileId - file ID
+    ///
+    /// Returns the file option
+    /// </pre>
+    #[inline]
+    pub fn get_file_by_id(id: FileId) -> Option<FileStruct<<T as frame_system::Config>::AccountId>> {
+        FileByID::<T>::get(id)
+    }
+
+    /// <pre>
+    /// Method: find_file_by_hash(hash: &H256) -> Option<(FileId, u32)>
+    /// Arguments: hash: &H256 - file hash
+    ///
+    /// Returns the file ID and version index which first registered the hash
+    /// </pre>
+    pub fn find_file_by_hash(hash: &H256) -> Option<(FileId, u32)> {
+        FileByHash::get(hash)
+    }
+
+    /// <pre>
+    /// Method: anchored_at(hash: &H256) -> Option<T::BlockNumber>
+    /// Arguments: hash: &H256 - anchored hash or Merkle root
+    ///
+    /// Returns the block in which the hash was anchored
+    /// </pre>
+    pub fn anchored_at(hash: &H256) -> Option<T::BlockNumber> {
+        Anchors::<T>::get(hash).map(|anchor| anchor.block)
+    }
+
+    /// <pre>
+    /// Method: verify_inclusion(root: &H256, leaf: &H256, proof: &[H256]) -> bool
+    /// Arguments: root, leaf, proof - Merkle root, member hash, sibling hashes from leaf to root
+    ///
+    /// Checks the Merkle inclusion proof, see merkle::generator for building proofs off chain
+    /// </pre>
+    pub fn verify_inclusion(root: &H256, leaf: &H256, proof: &[H256]) -> bool {
+        merkle::verify_inclusion(root, leaf, proof)
+    }
+
+    /// <pre>
+    /// Method: leaf_anchored_at(root: &H256, leaf: &H256, proof: &[H256]) -> Option<T::BlockNumber>
+    /// Arguments: root, leaf, proof - anchored batch root, member hash, inclusion proof
+    ///
+    /// Returns the block in which the batch containing the leaf was anchored
+    /// </pre>
+    pub fn leaf_anchored_at(root: &H256, leaf: &H256, proof: &[H256]) -> Option<T::BlockNumber> {
+        match Anchors::<T>::get(root) {
+            Some(anchor) if anchor.leaves > 0 && merkle::verify_inclusion(root, leaf, proof) => Some(anchor.block),
+            _ => None
+        }
+    }
+
+    /// <pre>
+    /// Method: verify_file_inclusion(id: FileId, version: u32, leaf: &H256, proof: &[H256]) -> bool
+    /// Arguments: id, version, leaf, proof - file ID, version index, member hash, inclusion proof
+    ///
+    /// Checks that the version filehash is a Merkle root committing to the leaf
+    /// </pre>
+    pub fn verify_file_inclusion(id: FileId, version: u32, leaf: &H256, proof: &[H256]) -> bool {
+        match FileByID::<T>::get(id) {
+            None => false,
+            Some(file) => match file.versions.get(version as usize) {
+                None => false,
+                Some(vers) => merkle::verify_inclusion(&vers.filehash, leaf, proof)
+            }
+        }
+    }
+
+    /// <pre>
+    /// Method: signing_payload(id: FileId, version: u32, signer: &T::AccountId) -> Vec<u8>
+    /// Arguments: id, version, signer - file ID, version index, signer
+    ///
+    /// Returns the payload the signer signs off chain for sign_with_payload, bound to the genesis hash
+    /// of the chain and the current signer nonce
+    /// </pre>
+    pub fn signing_payload(id: FileId, version: u32, signer: &T::AccountId) -> Vec<u8> {
+        let genesis_hash = <frame_system::Module<T>>::block_hash(T::BlockNumber::zero());
+        (SIGNING_PAYLOAD_PREFIX, genesis_hash, id, version, PayloadNonce::<T>::get(signer)).encode()
+    }
+
+    /// <pre>
+    /// Method: compute_file_id(owner: &T::AccountId, filehash: &H256, salt: &[u8]) -> FileId
+    /// Arguments: owner, filehash, salt - future file owner, hash of the first version, arbitrary salt
+    ///
+    /// Returns the file ID assigned in deterministic mode: blake2_128(owner ++ filehash ++ salt)
+    /// </pre>
+    pub fn compute_file_id(owner: &T::AccountId, filehash: &H256, salt: &[u8]) -> FileId {
+        let mut data = owner.encode();
+        data.extend_from_slice(filehash.as_bytes());
+        data.extend_from_slice(salt);
+        sp_io::hashing::blake2_128(&data)
+    }
+
+    /// <pre>
+    /// Method: salted_hash(salt: &[u8], document: &[u8]) -> H256
+    /// Arguments: salt, document - secret salt, document content
+    ///
+    /// Returns the salted filehash of a document: sha2_256(salt ++ document)
+    /// </pre>
+    pub fn salted_hash(salt: &[u8], document: &[u8]) -> H256 {
+        let mut data = salt.to_vec();
+        data.extend_from_slice(document);
+        H256::from(sp_io::hashing::sha2_256(&data))
+    }
+
+    /// <pre>
+    /// Method: verify_salted_document(id: FileId, version: u32, salt: &[u8], document: &[u8]) -> bool
+    /// Arguments: id, version, salt, document - file ID, version index, salt, document content
+    ///
+    /// Checks the document against the salted filehash of the version. The salt doesn't have to be
+    /// revealed on chain, a prover can share it with the verifier only, and a revealed salt is not
+    /// required to match the supplied one
+    /// </pre>
+    pub fn verify_salted_document(id: FileId, version: u32, salt: &[u8], document: &[u8]) -> bool {
+        match FileByID::<T>::get(id).and_then(|file| file.versions.get(version as usize).cloned()) {
+            Some(vers) => vers.salt != SaltState::Unsalted && vers.filehash == Self::salted_hash(salt, document),
+            None => false,
+        }
+    }
+
+    /// <pre>
+    /// Method: tag_commitment(tag: &[u8], salt: &[u8]) -> H256
+    /// Arguments: tag, salt - private tag, secret salt
+    ///
+    /// Returns the commitment stored as a tag of a private file: blake2_256(tag ++ salt)
+    /// </pre>
+    pub fn tag_commitment(tag: &[u8], salt: &[u8]) -> H256 {
+        let mut data = tag.to_vec();
+        data.extend_from_slice(salt);
+        H256::from(sp_io::hashing::blake2_256(&data))
+    }
+
+    /// <pre>
+    /// Method: verify_tag(id: FileId, version: u32, tag: &[u8], salt: &[u8]) -> bool
+    /// Arguments: id, version, tag, salt - file ID, version index, disclosed tag and salt
+    ///
+    /// Checks a tag disclosed in an envelope against the commitment of a private file version
+    /// </pre>
+    pub fn verify_tag(id: FileId, version: u32, tag: &[u8], salt: &[u8]) -> bool {
+        match FileByID::<T>::get(id) {
+            Some(file) if file.private => match file.versions.get(version as usize) {
+                Some(vers) => vers.tag == Self::tag_commitment(tag, salt).as_bytes(),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_file(
+        caller: T::AccountId,
+        tag: Vec<u8>,
+        filehash: H256,
+        location: Option<FileLocation>,
+        id_mode: FileIdMode,
+        policy: SigningPolicy,
+        private: bool,
+        salted: bool,
+    ) -> Result<FileId, DispatchError> {
+        if let Some(loc) = &location {
+            Self::validate_location(loc)?;
+        }
+
+        // Update last created file ID
+        let mut revealed = None;
+        let file_id = match id_mode {
+            FileIdMode::Random => Self::get_random_id(&caller, &filehash)?,
+            FileIdMode::Explicit(id) => {
+                ensure!(!ReservedIds::<T>::contains_key(id), Error::<T>::IdReserved);
+                id
+            }
+            FileIdMode::Deterministic(salt) => {
+                ensure!(salt.len() <= MAX_ID_SALT_LENGTH, Error::<T>::SaltTooLong);
+                let id = Self::compute_file_id(&caller, &filehash, &salt);
+                ensure!(!ReservedIds::<T>::contains_key(id), Error::<T>::IdReserved);
+                id
+            }
+            FileIdMode::Committed(secret) => {
+                ensure!(!secret.is_empty() && secret.len() <= MAX_ID_SECRET_LENGTH, Error::<T>::InvalidIdSecret);
+                let commitment = H256::from(sp_io::hashing::blake2_256(&secret));
+                let (committed_at, id) = IdCommitments::<T>::get(&caller, commitment).ok_or(Error::<T>::IdCommitmentNotFound)?;
+                ensure!(committed_at < <frame_system::Module<T>>::block_number(), Error::<T>::IdRevealTooEarly);
+                revealed = Some((commitment, id));
+                id
+            }
+        };
+        ensure!(<FileByID<T>>::get(file_id).is_none(), Error::<T>::IdAlreadyExists);
+        Self::check_hash_policy(&caller, &filehash)?;
+        if let Some((commitment, id)) = revealed {
+            IdCommitments::<T>::remove(&caller, commitment);
+            ReservedIds::<T>::remove(id);
+        }
+        let mut new_file = FileStruct::<<T as frame_system::Config>::AccountId>::new(caller.clone(), file_id, tag.clone(), &filehash, location.clone());
+        new_file.policy = policy;
+        new_file.private = private;
+        if salted {
+            new_file.versions[0].salt = SaltState::Committed;
+        }
+        <FileByID<T>>::insert(file_id, new_file);
+        if !FileByHash::contains_key(filehash) {
+            FileByHash::insert(filehash, (file_id, 0));
+        }
+        Self::deposit_event(RawEvent::FileCreated(caller.clone(), file_id, filehash, tag));
+        if let Some(loc) = location {
+            // salted version is queued once the salt is revealed
+            if !salted {
+                Self::queue_hash_check(file_id, 0);
+            }
+            Self::deposit_event(RawEvent::LocationSet(caller, file_id, 0, loc));
+        }
+        Ok(file_id)
+    }
+
+    // Signs the latest version, deposits signing events and calls the signing hooks
+    fn sign_file(id: FileId, signer: &T::AccountId, delegate: Option<T::AccountId>) -> DispatchResult {
+        ensure!(!Self::file_is_finalized(id), Error::<T>::FileFinalized);
+        let (version, filehash, tag, approved) = FileByID::<T>::try_mutate(
+            id, |file_option| {
+                match file_option {
+                    None => Err(Error::<T>::FileNotFound),
+                    Some(file) => {
+                        ensure!(Self::address_can_sign(file, signer), Error::<T>::AddressNotSigner);
+                        let was_approved = Self::version_is_approved(file);
+                        file.sign_latest_version(signer.clone(), delegate.clone())?;
+                        let version = (file.versions.len() - 1) as u32;
+                        let vers = &file.versions[version as usize];
+                        Ok((version, vers.filehash, vers.tag.clone(), Self::newly_approved(file, was_approved)))
+                    }
+                }
+            })?;
+
+        match delegate {
+            None => Self::deposit_event(RawEvent::FileSigned(signer.clone(), id, version, filehash, tag)),
+            Some(delegate) => Self::deposit_event(
+                RawEvent::FileSignedByDelegate(delegate, signer.clone(), id, version, filehash, tag)
+            ),
+        }
+        T::OnFileSigned::on_file_signed(id, version, signer);
+        Self::note_approval(id, approved);
+        Ok(())
+    }
+
+    // Latest version and its filehash if the version has become approved by the last change
+    fn newly_approved(file: &FileStruct<T::AccountId>, was_approved: bool) -> Option<(u32, H256)> {
+        if was_approved || !Self::version_is_approved(file) {
+            return None;
+        }
+        file.versions.last().map(|latest| ((file.versions.len() - 1) as u32, latest.filehash))
+    }
+
+    // Reports a newly approved version to clients and other pallets
+    fn note_approval(id: FileId, approved: Option<(u32, H256)>) {
+        if let Some((version, filehash)) = approved {
+            Self::deposit_event(RawEvent::SignatureCompleted(id, version, filehash));
+            T::OnVersionApproved::on_version_approved(id, version);
+        }
+    }
+
+    fn remove_signer(file: &mut FileStruct<T::AccountId>, signer: &T::AccountId, policy: RemovedSignerPolicy) -> Result<(), Error<T>> {
+        ensure!(file.signers.contains(signer), Error::<T>::AddressNotSigner);
+        match policy {
+            RemovedSignerPolicy::BlockRemoval => ensure!(!file.signed_any_version(signer), Error::<T>::SignerHasSigned),
+            RemovedSignerPolicy::Invalidate => file.invalidate_signatures(signer),
+            RemovedSignerPolicy::KeepHistorical => {}
+        }
+        file.delete_signer_from_file(signer.clone())?;
+        Ok(())
+    }
+
+    // Individual signers and members of assigned signer groups who accepted the invitation can sign
+    fn address_can_sign(file: &FileStruct<T::AccountId>, address: &T::AccountId) -> bool {
+        file.signers.iter().any(|x| x == address) ||
+            file.signer_groups.iter().any(|group| group.members.contains(address))
+    }
+
+    // Signing policy and every group threshold are met in the latest version
+    fn version_is_approved(file: &FileStruct<T::AccountId>) -> bool {
+        let latest = match file.versions.last() {
+            None => return false,
+            Some(version) => version
+        };
+        if file.signers.is_empty() && file.signer_groups.is_empty() {
+            return false;
+        }
+        let signers_satisfied = match file.policy {
+            SigningPolicy::All => file.signers.iter().all(|signer| file.latest_version_signed_by(signer)),
+            SigningPolicy::Threshold(threshold) => {
+                file.signers.iter().filter(|signer| file.latest_version_signed_by(signer)).count() as u32 >= threshold
+            }
+        };
+        signers_satisfied && file.signer_groups.iter().all(|group| group.is_satisfied(latest))
+    }
+
+    // Fails if adding the given number of signers to the file exceeds MaxSigners
+    fn check_signer_capacity(file: &FileStruct<T::AccountId>, added: usize) -> Result<(), Error<T>> {
+        ensure!(file.signer_count().saturating_add(added) <= T::MaxSigners::get() as usize, Error::<T>::TooManySigners);
+        Ok(())
+    }
+
+    fn validate_group(name: &[u8], mut members: Vec<T::AccountId>, threshold: u32) -> Result<Vec<T::AccountId>, Error<T>> {
+        ensure!(!name.is_empty() && name.len() <= MAX_GROUP_NAME_LENGTH, Error::<T>::InvalidGroupName);
+        members.sort();
+        members.dedup();
+        ensure!(!members.is_empty() && members.len() <= MAX_GROUP_MEMBERS, Error::<T>::InvalidGroupMembers);
+        ensure!(threshold > 0 && threshold as usize <= members.len(), Error::<T>::InvalidThreshold);
+        Ok(members)
+    }
+
+    fn payload_is_valid(id: FileId, version: u32, signer: &T::AccountId, signature: &T::PayloadSignature) -> bool {
+        let file = match FileByID::<T>::get(id) {
+            None => return false,
+            Some(file) => file
+        };
+        if file.versions.len().checked_sub(1) != Some(version as usize) || !Self::address_can_sign(&file, signer) {
+            return false;
+        }
+        // a payload that can no longer be applied must not be valid, the nonce is only bumped on success
+        if Self::file_is_finalized(id) || file.latest_version_signed_by(signer) {
+            return false;
+        }
+        signature.verify(&Self::signing_payload(id, version, signer)[..], signer)
+    }
+
+    fn insert_anchor(owner: T::AccountId, hash: H256, leaves: u32) -> DispatchResult {
+        ensure!(!Anchors::<T>::contains_key(hash), Error::<T>::AlreadyAnchored);
+        let block = <frame_system::Module<T>>::block_number();
+        Anchors::<T>::insert(hash, AnchorStruct { owner, block, leaves });
+        Ok(())
+    }
+
+    fn check_hash_policy(caller: &T::AccountId, filehash: &H256) -> DispatchResult {
+        let (registered_id, _) = match FileByHash::get(filehash) {
+            None => return Ok(()),
+            Some(entry) => entry
+        };
+        match T::HashPolicy::get() {
+            DuplicateHashPolicy::Allow => Ok(()),
+            DuplicateHashPolicy::Reject => Err(Error::<T>::HashAlreadyRegistered.into()),
+            DuplicateHashPolicy::AllowSameOwner => {
+                ensure!(Self::address_is_owner_for_file(registered_id, caller), Error::<T>::HashAlreadyRegistered);
+                Ok(())
+            }
+        }
+    }
+
+    fn validate_location(location: &FileLocation) -> DispatchResult {
+        ensure!(location.as_bytes().len() <= MAX_LOCATION_LENGTH, Error::<T>::LocationTooLong);
+        ensure!(location.is_valid(), Error::<T>::InvalidLocation);
+        Ok(())
+    }
+
+    // Generates an unused file id, the caller, filehash and nonce are mixed into the id itself
+    // so ids differ even when the randomness source returns the same value for every subject. Retries on collision
+    fn get_random_id(caller: &T::AccountId, filehash: &H256) -> Result<FileId, Error<T>> {
+        for _ in 0..MAX_RANDOM_ID_ATTEMPTS {
+            let nonce = Self::get_and_increment_nonce();
+            let rand = T::Randomness::random(&nonce);
+            let id = (rand, caller, filehash, nonce).using_encoded(sp_io::hashing::blake2_128);
+            if !FileByID::<T>::contains_key(id) && !ReservedIds::<T>::contains_key(id) {
+                return Ok(id);
+            }
+        }
+        Err(Error::<T>::IdAlreadyExists)
+    }
+
+    // The ID is reserved at commit time, later attempts skip IDs taken in front of the commitment
+    fn get_committed_id(caller: &T::AccountId, commitment: &H256) -> Result<FileId, Error<T>> {
+        for attempt in 0..MAX_RANDOM_ID_ATTEMPTS {
+            let id = (caller, commitment, attempt).using_encoded(sp_io::hashing::blake2_128);
+            if !FileByID::<T>::contains_key(id) && !ReservedIds::<T>::contains_key(id) {
+                return Ok(id);
+            }
+        }
+        Err(Error::<T>::IdAlreadyExists)
+    }
+
+    // Returns the account of the hash checker who signed the report
+    fn check_mismatch_report(payload: &MismatchPayload<T::Public, T::BlockNumber>) -> Result<T::AccountId, DispatchError> {
+        let checker = payload.public.clone().into_account();
+        ensure!(HashCheckers::<T>::get().contains(&checker), Error::<T>::NotHashChecker);
+        let file = FileByID::<T>::get(payload.id).ok_or(Error::<T>::FileNotFound)?;
+        let vers = file.versions.get(payload.version as usize).ok_or(Error::<T>::VersionNotFound)?;
+        ensure!(vers.location.is_some(), Error::<T>::NoLocation);
+        ensure!(!Mismatches::<T>::contains_key(payload.id, payload.version), Error::<T>::MismatchAlreadyReported);
+        let now = <frame_system::Module<T>>::block_number();
+        ensure!(payload.block_number.saturating_add(MISMATCH_REPORT_LONGEVITY.into()) >= now, Error::<T>::MismatchReportOutdated);
+        if let Some(cleared_at) = MismatchClearedAt::<T>::get(payload.id, payload.version) {
+            ensure!(payload.block_number > cleared_at, Error::<T>::MismatchReportOutdated);
+        }
+        Ok(checker)
+    }
+
+    // Removes the mismatch report of the version, reports signed up to now are rejected afterwards
+    fn reset_mismatch(id: FileId, version: u32) {
+        Mismatches::<T>::remove(id, version);
+        MismatchClearedAt::<T>::insert(id, version, <frame_system::Module<T>>::block_number());
+    }
+
+    // Local keystore keys of registered hash checkers
+    fn local_hash_checker_keys() -> Vec<T::Public> {
+        let checkers = HashCheckers::<T>::get();
+        <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+            .into_iter()
+            .map(|key| {
+                let generic: <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic = key.into();
+                let public: T::Public = generic.into();
+                public
+            })
+            .filter(|public| checkers.contains(&public.clone().into_account()))
+            .collect()
+    }
+
+    // Fetches documents behind version locations and reports the ones not matching filehash,
+    // filehash is expected to be sha2_256 of the document, salted versions are checked with the revealed salt
+    // and versions which filehash is an anchored batch root are skipped
+    // Checks the versions queued since the last run, at most MAX_HASH_CHECKS_PER_RUN of them, a version whose
+    // download failed is retried in the next runs until MAX_FETCH_ATTEMPTS
+    fn check_remote_hashes(now: T::BlockNumber) {
+        // only nodes holding a hash checker key download documents
+        let keys = Self::local_hash_checker_keys();
+        if keys.is_empty() {
+            return;
+        }
+        let hosts = match StorageValueRef::persistent(FETCH_HOSTS_KEY).get::<Vec<Vec<u8>>>() {
+            Some(Some(hosts)) => hosts,
+            _ => Vec::new(),
+        };
+        let cursor = StorageValueRef::persistent(HASH_CHECK_CURSOR_KEY);
+        let (mut position, mut failures) = match cursor.get::<(u64, u32)>() {
+            Some(Some(cursor)) => cursor,
+            _ => (0, 0),
+        };
+        let end = core::cmp::min(HashCheckCount::get(), position.saturating_add(MAX_HASH_CHECKS_PER_RUN));
+        while position < end {
+            let key = Self::hash_check_key(position);
+            let mut entry = StorageValueRef::persistent(&key);
+            if let Some(Some((id, version))) = entry.get::<(FileId, u32)>() {
+                if let Err(e) = Self::check_remote_hash(&keys, &hosts, id, version, now) {
+                    debug::warn!("filesign: failed to fetch document: {:?}", e);
+                    failures += 1;
+                    if failures < MAX_FETCH_ATTEMPTS {
+                        break;
+                    }
+                }
+                entry.clear();
+            }
+            position += 1;
+            failures = 0;
+        }
+        cursor.set(&(position, failures));
+    }
+
+    // Downloads the document behind the version location and reports a hash mismatch
+    fn check_remote_hash(keys: &[T::Public], hosts: &[Vec<u8>], id: FileId, version: u32, now: T::BlockNumber) -> Result<(), http::Error> {
+        let file = match FileByID::<T>::get(id) {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        let vers = match file.versions.get(version as usize) {
+            Some(vers) => vers,
+            None => return Ok(()),
+        };
+        // salted version is queued again once the salt is revealed, a Merkle root is never a hash of the document
+        let location = match &vers.location {
+            Some(location) if vers.salt != SaltState::Committed && !Self::is_batch_root(&vers.filehash)
+                && !Mismatches::<T>::contains_key(id, version) => location,
+            _ => return Ok(()),
+        };
+        if let Some(document) = Self::fetch_location(location, hosts)? {
+            let hash = match &vers.salt {
+                SaltState::Revealed(salt) => Self::salted_hash(salt, &document),
+                _ => H256::from(sp_io::hashing::sha2_256(&document)),
+            };
+            if hash != vers.filehash {
+                Self::submit_mismatch_report(keys.to_vec(), id, version, now);
+            }
+        }
+        Ok(())
+    }
+
+    // Checks if the hash is anchored as the Merkle root of a batch
+    fn is_batch_root(hash: &H256) -> bool {
+        matches!(Anchors::<T>::get(hash), Some(anchor) if anchor.leaves > 0)
+    }
+
+    fn submit_mismatch_report(keys: Vec<T::Public>, id: FileId, version: u32, now: T::BlockNumber) {
+        let result = Signer::<T, T::AuthorityId>::any_account()
+            .with_filter(keys)
+            .send_unsigned_transaction(
+                |account| MismatchPayload { id, version, block_number: now, public: account.public.clone() },
+                |payload, signature| Call::report_mismatch(payload, signature),
+            );
+        if !matches!(result, Some((_, Ok(())))) {
+            debug::warn!("filesign: failed to submit mismatch report");
+        }
+    }
+
+    // Downloads the document, None for locations without a public endpoint and URLs of hosts not allowed
+    // under FETCH_HOSTS_KEY
+    fn fetch_location(location: &FileLocation, hosts: &[Vec<u8>]) -> Result<Option<Vec<u8>>, http::Error> {
+        let url = match location {
+            FileLocation::Url(url) => match location.url_host() {
+                Some(host) if hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host)) => url.clone(),
+                _ => return Ok(None),
+            },
+            FileLocation::Ipfs(cid) => {
+                let mut url = match StorageValueRef::persistent(IPFS_GATEWAY_KEY).get::<Vec<u8>>() {
+                    Some(Some(gateway)) => gateway,
+                    _ => DEFAULT_IPFS_GATEWAY.to_vec(),
+                };
+                url.extend_from_slice(cid);
+                url
+            }
+            FileLocation::S3(_) => return Ok(None),
+        };
+        let url = core::str::from_utf8(&url).map_err(|_| http::Error::Unknown)?;
+        let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+        let pending = http::Request::get(url)
+            .deadline(deadline)
+            .send()
+            .map_err(|_| http::Error::IoError)?;
+        let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+        if response.code != 200 {
+            return Err(http::Error::Unknown);
+        }
+        Ok(Some(response.body().collect()))
+    }
+
+    // Posts the notifications indexed while the block was imported, batches are numbered from 0
+    // without gaps, so the first missing batch ends the block
+    fn send_notifications(now: T::BlockNumber) {
+        let url = match StorageValueRef::persistent(WEBHOOK_URL_KEY).get::<Vec<u8>>() {
+            Some(Some(url)) => url,
+            _ => return,
+        };
+        for batch in 0.. {
+            let key = Self::notifications_key(now, batch);
+            let mut storage = StorageValueRef::persistent(&key);
+            let notifications = match storage.get::<Vec<Notification<T::AccountId, T::BlockNumber>>>() {
+                Some(Some(notifications)) => notifications,
+                _ => break,
+            };
+            for notification in notifications.iter() {
+                if let Err(e) = Self::send_notification(&url, notification) {
+                    debug::warn!("filesign: webhook notification failed: {:?}", e);
+                }
+            }
+            storage.clear();
+        }
+    }
+
+    // Hands notifications to the off-chain worker through the off-chain index, no on-chain state is written
+    fn index_notifications(notifications: Vec<Notification<T::AccountId, T::BlockNumber>>) {
+        if notifications.is_empty() {
+            return;
+        }
+        let block = <frame_system::Module<T>>::block_number();
+        let batch = NotificationBatches::get();
+        sp_io::offchain_index::set(&Self::notifications_key(block, batch), &notifications.encode());
+        NotificationBatches::put(batch + 1);
+    }
+
+    fn notifications_key(block: T::BlockNumber, batch: u32) -> Vec<u8> {
+        (NOTIFICATIONS_KEY_PREFIX, block, batch).encode()
+    }
+
+    // Queues the version for a hash check by the off-chain worker
+    fn queue_hash_check(id: FileId, version: u32) {
+        let position = HashCheckCount::get();
+        sp_io::offchain_index::set(&Self::hash_check_key(position), &(id, version).encode());
+        HashCheckCount::put(position + 1);
+    }
+
+    fn hash_check_key(position: u64) -> Vec<u8> {
+        (HASH_CHECK_KEY_PREFIX, position).encode()
+    }
+
+    // Posts the notification as JSON, any 2xx response is a success
+    fn send_notification(url: &[u8], notification: &Notification<T::AccountId, T::BlockNumber>) -> Result<(), http::Error> {
+        let url = core::str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+        let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(WEBHOOK_TIMEOUT_MS));
+        let pending = http::Request::post(url, vec![Self::notification_json(notification)])
+            .add_header("Content-Type", "application/json")
+            .deadline(deadline)
+            .send()
+            .map_err(|_| http::Error::IoError)?;
+        let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+        if response.code < 200 || response.code >= 300 {
+            return Err(http::Error::Unknown);
+        }
+        Ok(())
+    }
+
+    // Renders the notification as a JSON object, ids and accounts as hex of their SCALE encoding
+    fn notification_json(notification: &Notification<T::AccountId, T::BlockNumber>) -> Vec<u8> {
+        fn push_hex(json: &mut Vec<u8>, bytes: &[u8]) {
+            const DIGITS: &[u8; 16] = b"0123456789abcdef";
+            json.extend_from_slice(b"\"0x");
+            for byte in bytes {
+                json.push(DIGITS[(byte >> 4) as usize]);
+                json.push(DIGITS[(byte & 0x0f) as usize]);
+            }
+            json.push(b'"');
+        }
+
+        let mut json = Vec::new();
+        match notification {
+            Notification::SignerInvited(id, signer) => {
+                json.extend_from_slice(b"{\"event\":\"SignerInvited\",\"file\":");
+                push_hex(&mut json, id);
+                json.extend_from_slice(b",\"signer\":");
+                push_hex(&mut json, &signer.encode());
+            }
+            Notification::DelegationExpired(principal, delegate, file, block) => {
+                json.extend_from_slice(b"{\"event\":\"DelegationExpired\",\"principal\":");
+                push_hex(&mut json, &principal.encode());
+                json.extend_from_slice(b",\"delegate\":");
+                push_hex(&mut json, &delegate.encode());
+                json.extend_from_slice(b",\"file\":");
+                match file {
+                    Some(id) => push_hex(&mut json, id),
+                    None => json.extend_from_slice(b"null"),
+                }
+                json.extend_from_slice(b",\"block\":");
+                let block: u64 = (*block).unique_saturated_into();
+                let mut digits = Vec::new();
+                let mut rest = block;
+                loop {
+                    digits.push(b'0' + (rest % 10) as u8);
+                    rest /= 10;
+                    if rest == 0 {
+                        break;
+                    }
+                }
+                json.extend(digits.iter().rev());
+            }
+        }
+        json.push(b'}');
+        json
+    }
+
+    fn get_and_increment_nonce() -> Vec<u8> {
+        let nonce = NonceId::get();
+        NonceId::put(nonce.wrapping_add(1));
+        nonce.encode()
+    }
+}
+
+impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+        match call {
+            Call::sign_with_payload(id, version, signer, signature) => {
+                if !Self::payload_is_valid(*id, *version, signer, signature) {
+                    return InvalidTransaction::BadProof.into();
+                }
+                ValidTransaction::with_tag_prefix("FilesignPayload")
+                    .and_provides((signer.clone(), PayloadNonce::<T>::get(signer)))
+                    .longevity(64)
+                    .propagate(true)
+                    .build()
+            }
+            Call::report_mismatch(payload, signature) => {
+                // checked for every source, a block author can't include a report without a checker signature
+                if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                    return InvalidTransaction::BadProof.into();
+                }
+                if payload.block_number > <frame_system::Module<T>>::block_number() {
+                    return InvalidTransaction::Future.into();
+                }
+                match Self::check_mismatch_report(payload) {
+                    Err(e) if e == DispatchError::from(Error::<T>::NotHashChecker) => return InvalidTransaction::BadProof.into(),
+                    Err(_) => return InvalidTransaction::Stale.into(),
+                    Ok(_) => {}
+                }
+                let age = <frame_system::Module<T>>::block_number().saturating_sub(payload.block_number);
+                ValidTransaction::with_tag_prefix("FilesignMismatch")
+                    .and_provides((payload.id, payload.version, payload.block_number))
+                    .longevity((MISMATCH_REPORT_LONGEVITY as u64).saturating_sub(age.unique_saturated_into()).max(1))
+                    .propagate(true)
+                    .build()
+            }
+            _ => InvalidTransaction::Call.into(),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::file::H256;
+use frame_support::dispatch::Vec;
+
+// Leaves and inner nodes are hashed with distinct prefixes, so an inner node
+// can't be presented as a leaf. Pairs are sorted before hashing, so a proof is
+// just the list of siblings from the leaf up to the root.
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+fn hash_leaf(leaf: &H256) -> H256 {
+    let mut data = [0u8; 33];
+    data[0] = LEAF_PREFIX;
+    data[1..].copy_from_slice(leaf.as_bytes());
+    H256::from(sp_io::hashing::blake2_256(&data))
+}
+
+fn hash_node(a: &H256, b: &H256) -> H256 {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = [0u8; 65];
+    data[0] = NODE_PREFIX;
+    data[1..33].copy_from_slice(first.as_bytes());
+    data[33..].copy_from_slice(second.as_bytes());
+    H256::from(sp_io::hashing::blake2_256(&data))
+}
+
+/// Checks that `leaf` is a member of the tree with the given `root`
+pub fn verify_inclusion(root: &H256, leaf: &H256, proof: &[H256]) -> bool {
+    let computed = proof.iter().fold(hash_leaf(leaf), |acc, sibling| hash_node(&acc, sibling));
+    computed == *root
+}
+
+/// Off-chain construction of roots and inclusion proofs
+#[cfg(feature = "std")]
+pub mod generator {
+    use super::*;
+
+    // Odd node at the end of a layer is promoted unchanged
+    fn next_layer(layer: &[H256]) -> Vec<H256> {
+        layer.chunks(2)
+            .map(|pair| if pair.len() == 2 { hash_node(&pair[0], &pair[1]) } else { pair[0] })
+            .collect()
+    }
+
+    /// Merkle root of the given leaves, None for an empty set
+    pub fn merkle_root(leaves: &[H256]) -> Option<H256> {
+        let mut layer: Vec<H256> = leaves.iter().map(hash_leaf).collect();
+        while layer.len() > 1 {
+            layer = next_layer(&layer);
+        }
+        layer.pop()
+    }
+
+    /// Inclusion proof for the leaf at `index`, None if out of range
+    pub fn merkle_proof(leaves: &[H256], index: usize) -> Option<Vec<H256>> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut layer: Vec<H256> = leaves.iter().map(hash_leaf).collect();
+        let mut index = index;
+        let mut proof = Vec::new();
+        while layer.len() > 1 {
+            let sibling = index ^ 1;
+            if sibling < layer.len() {
+                proof.push(layer[sibling]);
+            }
+            layer = next_layer(&layer);
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
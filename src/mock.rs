@@ -1,11 +1,23 @@
 #![allow(clippy::from_over_into)]
 
 use crate as pallet_filesign;
+use crate::file::{DuplicateHashPolicy, FileId, RemovedSignerPolicy};
+use crate::{GenesisFile, OnFileSigned, OnVersionApproved};
+use frame_support::traits::Get;
+use std::cell::RefCell;
+use std::sync::Arc;
+use parking_lot::RwLock;
+use sp_core::offchain::{
+	testing::{OffchainState, PoolState, TestOffchainExt, TestTransactionPoolExt},
+	OffchainExt, TransactionPoolExt,
+};
 
+use frame_support::parameter_types;
 use frame_support::sp_runtime::{
-    testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
+    testing::{Header, TestSignature, TestXt, UintAuthorityId},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
 };
+use frame_system::offchain::AppCrypto;
 use sp_core::H256;
 
 
@@ -21,7 +33,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
-		Filesign: pallet_filesign::{Module, Call, Storage, Event<T>},
+		Filesign: pallet_filesign::{Module, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
 	}
 );
 
@@ -50,14 +62,145 @@ impl frame_system::Config for TestRuntime {
 	type SS58Prefix = ();
 }
 
+thread_local! {
+	static HASH_POLICY: RefCell<DuplicateHashPolicy> = const { RefCell::new(DuplicateHashPolicy::Reject) };
+}
+
+pub struct HashPolicy;
+impl Get<DuplicateHashPolicy> for HashPolicy {
+	fn get() -> DuplicateHashPolicy {
+		HASH_POLICY.with(|v| *v.borrow())
+	}
+}
+
+// Switch duplicate hash policy for the current test thread
+pub fn set_hash_policy(policy: DuplicateHashPolicy) {
+	HASH_POLICY.with(|v| *v.borrow_mut() = policy);
+}
+
+thread_local! {
+	static REMOVAL_POLICY: RefCell<RemovedSignerPolicy> = const { RefCell::new(RemovedSignerPolicy::KeepHistorical) };
+}
+
+pub struct SignerRemovalPolicy;
+impl Get<RemovedSignerPolicy> for SignerRemovalPolicy {
+	fn get() -> RemovedSignerPolicy {
+		REMOVAL_POLICY.with(|v| *v.borrow())
+	}
+}
+
+// Switch removed signer policy for the current test thread
+pub fn set_removal_policy(policy: RemovedSignerPolicy) {
+	REMOVAL_POLICY.with(|v| *v.borrow_mut() = policy);
+}
+
+thread_local! {
+	static SIGNED_FILES: RefCell<Vec<(FileId, u32, u64)>> = const { RefCell::new(Vec::new()) };
+	static APPROVED_VERSIONS: RefCell<Vec<(FileId, u32)>> = const { RefCell::new(Vec::new()) };
+}
+
+// Records hook calls for the current test thread
+pub struct TestHooks;
+impl OnFileSigned<u64> for TestHooks {
+	fn on_file_signed(id: FileId, version: u32, signer: &u64) {
+		SIGNED_FILES.with(|v| v.borrow_mut().push((id, version, *signer)));
+	}
+}
+impl OnVersionApproved for TestHooks {
+	fn on_version_approved(id: FileId, version: u32) {
+		APPROVED_VERSIONS.with(|v| v.borrow_mut().push((id, version)));
+	}
+}
+
+pub fn signed_files() -> Vec<(FileId, u32, u64)> {
+	SIGNED_FILES.with(|v| v.borrow().clone())
+}
+
+pub fn approved_versions() -> Vec<(FileId, u32)> {
+	APPROVED_VERSIONS.with(|v| v.borrow().clone())
+}
+
 impl pallet_filesign::Config for TestRuntime {
 	type Event = Event;
+	type AuthorityId = TestAuthId;
 	type Randomness = RandomnessCollectiveFlip;
+	type HashPolicy = HashPolicy;
+	type SigningPublicKey = UintAuthorityId;
+	type PayloadSignature = TestSignature;
+	type SignerRemovalPolicy = SignerRemovalPolicy;
+	type MaxSigners = MaxSigners;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type OnFileSigned = TestHooks;
+	type OnVersionApproved = TestHooks;
+	type HashCheckInterval = HashCheckInterval;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const HashCheckInterval: u64 = 10;
+	pub const MaxSigners: u32 = 64;
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for TestRuntime where Call: From<C> {
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl frame_system::offchain::SigningTypes for TestRuntime {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<C> frame_system::offchain::CreateSignedTransaction<C> for TestRuntime where Call: From<C> {
+	fn create_transaction<A: AppCrypto<UintAuthorityId, TestSignature>>(
+		call: Call,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+// Hash checker keys of the off-chain worker, set the local keys with UintAuthorityId::set_all_keys
+pub struct TestAuthId;
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> frame_support::sp_io::TestExternalities {
-	frame_system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap().into()
+	ExtBuilder::default().build()
+}
+
+// Genesis storage builder with pre-registered files
+#[derive(Default)]
+pub struct ExtBuilder {
+	files: Vec<GenesisFile<u64>>,
+}
+
+impl ExtBuilder {
+	pub fn with_file(mut self, file: GenesisFile<u64>) -> Self {
+		self.files.push(file);
+		self
+	}
+
+	pub fn build(self) -> frame_support::sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		pallet_filesign::GenesisConfig::<TestRuntime> {
+			hash_checkers: Vec::new(),
+			files: self.files,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		t.into()
+	}
 }
 
 // Build genesis storage for event testing
@@ -71,6 +214,17 @@ pub fn new_test_ext_with_event() -> frame_support::sp_io::TestExternalities {
 	ext
 }
 
+// Build genesis storage with the off-chain and transaction pool test extensions for the off-chain worker,
+// the off-chain index is readable by the worker after persist_offchain_overlay
+pub fn new_test_ext_with_offchain() -> (frame_support::sp_io::TestExternalities, Arc<RwLock<OffchainState>>, Arc<RwLock<PoolState>>) {
+	let mut ext = new_test_ext_with_event();
+	let (offchain, state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	(ext, state, pool_state)
+}
+
 // get and cut last event
 #[allow(clippy::result_unit_err)] 
 pub fn last_event() -> Result<Event, ()> {
@@ -78,4 +232,9 @@ pub fn last_event() -> Result<Event, ()> {
 		Some(ev) => Ok(ev.event),
 		None => Err(())
 	}
-}
\ No newline at end of file
+}
+
+// check that the event has been deposited
+pub fn event_deposited(event: Event) -> bool {
+	System::events().iter().any(|record| record.event == event)
+}
@@ -0,0 +1,22 @@
+// the generated client side API functions take more than seven arguments
+#![allow(clippy::too_many_arguments)]
+
+use crate::file::{FileId, FileStruct, H256};
+use codec::Codec;
+use frame_support::dispatch::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Read access to files for RPC and off-chain clients
+    pub trait FilesignApi<AccountId: Codec + PartialEq> {
+        /// File with all versions, signers and signatures
+        fn get_file_by_id(id: FileId) -> Option<FileStruct<AccountId>>;
+        /// First file and version registering the hash
+        fn find_file_by_hash(hash: H256) -> Option<(FileId, u32)>;
+        /// Checks if the address has signed the latest version of the file
+        fn address_has_signed_the_file(id: FileId, address: AccountId) -> bool;
+        /// Checks if the latest version of the file is approved
+        fn latest_version_is_signed(id: FileId) -> bool;
+        /// Payload the signer has to sign off chain for sign_with_payload
+        fn signing_payload(id: FileId, version: u32, signer: AccountId) -> Vec<u8>;
+    }
+}
@@ -1,6 +1,19 @@
 use crate::mock::*;
 use crate::H256;
-use frame_support::{assert_ok, assert_noop, dispatch::{
+use crate::merkle::generator::{merkle_root, merkle_proof};
+use crate::file::{DuplicateHashPolicy, FileError, FileIdMode, FileStruct, Notification, RemovedSignerPolicy, SigningPolicy, FileLocation, SaltState, MAX_ENVELOPE_LENGTH, MAX_FILE_GROUPS, MAX_HASH_SALT_LENGTH, MAX_ID_SALT_LENGTH, MAX_LOCATION_LENGTH, MAX_ATTRIBUTES, MAX_ATTRIBUTE_KEY_LENGTH};
+use frame_support::sp_runtime::{
+	DispatchError::BadOrigin,
+	offchain::storage::StorageValueRef,
+	testing::{TestSignature, UintAuthorityId},
+	traits::ValidateUnsigned,
+	transaction_validity::TransactionSource,
+};
+use frame_support::traits::{OffchainWorker, OnFinalize, OnInitialize};
+use frame_system::offchain::SignedPayload;
+use sp_core::offchain::testing::PendingRequest;
+use codec::{Decode, Encode};
+use frame_support::{assert_ok, assert_noop, StorageMap, dispatch::{
 		DispatchResult, 
 		Vec,
 }};
@@ -19,7 +32,7 @@ fn it_works_for_create_new_file() {
 		let owner = 3;
 		let file_id = generate_file_id();
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag, filehash, Some(file_id));
+		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let file_option = Filesign::get_file_by_id(file_id);
 		
 		assert!(file_option.is_some());
@@ -43,8 +56,8 @@ fn it_works_for_create_new_file_id_already_exists() {
 		let second_owner = 4;
 		let file_id = generate_file_id();
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag.clone(), filehash, Some(file_id));
-		let create_second_file_result = Filesign::create_new_file(Origin::signed(second_owner), tag, filehash, Some(file_id));
+		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let create_second_file_result = Filesign::create_new_file(Origin::signed(second_owner), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let file_option = Filesign::get_file_by_id(file_id);
 		
 		assert!(file_option.is_some());
@@ -67,7 +80,7 @@ fn it_works_for_create_new_file_no_file_id() {
 		let filehash = H256::from([0x66; 32]);
 		let owner = 3;
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag, filehash, None);
+		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag, filehash, None, FileIdMode::Random, false);
 
 		let event = last_event().unwrap();
 
@@ -75,7 +88,7 @@ fn it_works_for_create_new_file_no_file_id() {
 		let file_id = match event {
 			Event::pallet_filesign(e) => {
 				match e {
-					crate::RawEvent::FileCreated(_, id) => {
+					crate::RawEvent::FileCreated(_, id, _, _) => {
 						id
 					},
 					_ => panic!("event not right")
@@ -107,7 +120,7 @@ fn it_fails_for_create_new_file_incorrect_file_input() {
 		let owner = 3;
 		let file_id = generate_file_id();
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag, filehash, Some(file_id));		
+		let create_file_result = Filesign::create_new_file(Origin::signed(owner), tag, filehash, None, FileIdMode::Explicit(file_id), false);		
 		let file_opt = Filesign::get_file_by_id(file_id);
 
 		assert!(file_opt.is_none());
@@ -123,8 +136,9 @@ fn it_works_assign_signer() {
 		let account_id = 1;
 		let file_id = generate_file_id();
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(1), tag, filehash, Some(file_id));
+		let create_file_result = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let assign_signer_result = Filesign::assign_signer(Origin::signed(1), file_id, account_id);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(account_id), file_id));
 		let file_opt = Filesign::get_file_by_id(file_id);
 
 		assert!(file_opt.is_some());
@@ -145,8 +159,9 @@ fn it_works_assign_signer_do_no_dublicates() {
 		let account_id = 2;
 		let file_id = generate_file_id();
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(1), tag, filehash, Some(file_id));
+		let create_file_result = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let assign_signer_result = Filesign::assign_signer(Origin::signed(1), file_id, account_id);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(account_id), file_id));
 
 		// Try Dublicate:
 		let _ = Filesign::assign_signer(Origin::signed(1), file_id, account_id);
@@ -172,8 +187,9 @@ fn it_works_delete_signer() {
 		let account_id = 2;
 		let file_id = generate_file_id();
 
-		let create_file_result = Filesign::create_new_file(Origin::signed(1), tag, filehash, Some(file_id));
+		let create_file_result = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let assign_signer_result = Filesign::assign_signer(Origin::signed(1), file_id, account_id);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(account_id), file_id));
 
 		// Check file state before delete
 		let file_with_signer_opt = Filesign::get_file_by_id(file_id);
@@ -204,13 +220,14 @@ fn it_fails_delete_signer_no_signers() {
 		let file_id = generate_file_id();
 
 		let account_id = 1;
-		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, Some(file_id));
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 
 		// First - try to delete unexisting signer 
 		let delete_signer_result_no_signers = Filesign::delete_signer(Origin::signed(1), file_id, account_id);
 
 		// Second - try to delete unexisting signer after delete:
 		let _ = Filesign::assign_signer(Origin::signed(1), file_id, account_id);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(account_id), file_id));
 		let _ = Filesign::delete_signer(Origin::signed(1), file_id, account_id);
 		let delete_signer_result_after_delete = Filesign::delete_signer(Origin::signed(1), file_id, account_id);
 
@@ -228,8 +245,9 @@ fn it_works_sign_latest_version() {
 		let account_id = 1;
 		let file_id = generate_file_id();
 
-		let _ = Filesign::create_new_file(Origin::signed(account_id), tag, filehash, Some(file_id));
+		let _ = Filesign::create_new_file(Origin::signed(account_id), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let assign_signer_result = Filesign::assign_signer(Origin::signed(account_id), file_id, account_id);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(account_id), file_id));
 		let sign_latest_version_result = Filesign::sign_latest_version(Origin::signed(account_id), file_id);
 		let _ = Filesign::sign_latest_version(Origin::signed(account_id), file_id);
 		let file_opt = Filesign::get_file_by_id(file_id);
@@ -251,7 +269,7 @@ fn it_fail_sign_latest_version_not_an_signer() {
 		let file_id = generate_file_id();
 		let account_id = 1;
 
-		let _ = Filesign::create_new_file(Origin::signed(account_id), tag, filehash, Some(file_id));
+		let _ = Filesign::create_new_file(Origin::signed(account_id), tag, filehash, None, FileIdMode::Explicit(file_id), false);
 		let sign_latest_version_result = Filesign::sign_latest_version(Origin::signed(account_id), file_id);
 		let file_opt = Filesign::get_file_by_id(file_id);
 
@@ -262,4 +280,1763 @@ fn it_fail_sign_latest_version_not_an_signer() {
 		// Assert that no sign has been added
 		assert_eq!(0, file.versions.last().unwrap().signatures.len());
 	});
-}
\ No newline at end of file
+}
+#[test]
+fn it_works_for_create_new_file_with_location() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let location = FileLocation::Ipfs(b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec());
+
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), tag, filehash, Some(location.clone()), FileIdMode::Explicit(file_id), false));
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+
+		assert_eq!(Some(location.clone()), file.versions[0].location);
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::LocationSet(1, file_id, 0, location)), last_event().unwrap());
+	});
+}
+
+#[test]
+fn it_fails_for_create_new_file_invalid_location() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let bad_cid = FileLocation::Ipfs(b"Qm0OIl".to_vec());
+		let bad_url = FileLocation::Url(b"ftp://example.com/doc.pdf".to_vec());
+		let long_key = FileLocation::S3(vec![b'a'; MAX_LOCATION_LENGTH + 1]);
+
+		assert_noop!(Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, Some(bad_cid), FileIdMode::Explicit(file_id), false),
+			RuntimeError::InvalidLocation);
+		assert_noop!(Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, Some(bad_url), FileIdMode::Explicit(file_id), false),
+			RuntimeError::InvalidLocation);
+		assert_noop!(Filesign::create_new_file(Origin::signed(1), tag, filehash, Some(long_key), FileIdMode::Explicit(file_id), false),
+			RuntimeError::LocationTooLong);
+	});
+}
+
+#[test]
+fn it_works_for_location_cid_validation() {
+	assert!(FileLocation::Ipfs(b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec()).is_valid());
+	assert!(FileLocation::Ipfs(b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec()).is_valid());
+	assert!(FileLocation::Url(b"https://example.com/doc.pdf".to_vec()).is_valid());
+	// '0' is not a part of base58 alphabet
+	assert!(!FileLocation::Ipfs(b"Qm0wAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec()).is_valid());
+	// uppercase is not a part of base32 lowercase alphabet
+	assert!(!FileLocation::Ipfs(b"bAFYBEIGDYRZT5SFP7UDM7HU76UH7Y26NF3EFUYLQABF3OCLGTQY55FBZDI".to_vec()).is_valid());
+	assert!(!FileLocation::S3(Vec::new()).is_valid());
+}
+
+#[test]
+fn it_works_for_location_url_host() {
+	assert_eq!(Some(&b"example.com"[..]), FileLocation::Url(b"https://example.com/doc.pdf".to_vec()).url_host());
+	assert_eq!(Some(&b"localhost"[..]), FileLocation::Url(b"http://localhost:8080?doc".to_vec()).url_host());
+	assert_eq!(Some(&b"example.com"[..]), FileLocation::Url(b"https://example.com#doc".to_vec()).url_host());
+	// user info could hide the host from a reader of the URL
+	assert_eq!(None, FileLocation::Url(b"https://example.com@localhost/doc.pdf".to_vec()).url_host());
+	assert_eq!(None, FileLocation::Url(b"http://[::1]/doc.pdf".to_vec()).url_host());
+	assert_eq!(None, FileLocation::Url(b"https:///doc.pdf".to_vec()).url_host());
+	assert_eq!(None, FileLocation::S3(b"bucket/doc.pdf".to_vec()).url_host());
+}
+
+#[test]
+fn it_works_set_and_clear_attribute() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::set_attribute(Origin::signed(1), file_id, b"doctype".to_vec(), b"contract".to_vec()));
+		assert_ok!(Filesign::set_attribute(Origin::signed(1), file_id, b"number".to_vec(), b"42".to_vec()));
+		// overwrite keeps a single entry for the key
+		assert_ok!(Filesign::set_attribute(Origin::signed(1), file_id, b"number".to_vec(), b"43".to_vec()));
+
+		assert_eq!(Some(b"contract".to_vec()), Filesign::file_attribute(file_id, b"doctype"));
+		assert_eq!(Some(b"43".to_vec()), Filesign::file_attribute(file_id, b"number"));
+		assert_eq!(2, Filesign::get_file_by_id(file_id).unwrap().attributes.len());
+
+		assert_ok!(Filesign::clear_attribute(Origin::signed(1), file_id, b"doctype".to_vec()));
+		assert_eq!(None, Filesign::file_attribute(file_id, b"doctype"));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::AttributeCleared(1, file_id, b"doctype".to_vec())), last_event().unwrap());
+	});
+}
+
+#[test]
+fn it_fails_set_attribute_not_owner_or_out_of_bounds() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+
+		assert_noop!(Filesign::set_attribute(Origin::signed(2), file_id, b"doctype".to_vec(), b"contract".to_vec()),
+			RuntimeError::AddressNotOwner);
+		assert_noop!(Filesign::set_attribute(Origin::signed(1), file_id, Vec::new(), b"contract".to_vec()),
+			RuntimeError::EmptyAttributeKey);
+		assert_noop!(Filesign::set_attribute(Origin::signed(1), file_id, vec![1; MAX_ATTRIBUTE_KEY_LENGTH + 1], Vec::new()),
+			RuntimeError::AttributeKeyTooLong);
+		assert_noop!(Filesign::clear_attribute(Origin::signed(1), file_id, b"doctype".to_vec()),
+			RuntimeError::AttributeNotFound);
+
+		for i in 0..MAX_ATTRIBUTES {
+			assert_ok!(Filesign::set_attribute(Origin::signed(1), file_id, vec![i as u8 + 1], Vec::new()));
+		}
+		assert_noop!(Filesign::set_attribute(Origin::signed(1), file_id, b"overflow".to_vec(), Vec::new()),
+			RuntimeError::TooManyAttributes);
+	});
+}
+
+#[test]
+fn it_works_for_create_new_file_deterministic_id() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let owner = 3;
+		let salt = b"contract-42".to_vec();
+
+		let expected_id = Filesign::compute_file_id(&owner, &filehash, &salt);
+		assert_ok!(Filesign::create_new_file(Origin::signed(owner), tag.clone(), filehash, None, FileIdMode::Deterministic(salt.clone()), false));
+
+		let file = Filesign::get_file_by_id(expected_id).unwrap();
+		assert_eq!(owner, file.owner);
+		assert_eq!(expected_id, file.id);
+
+		// same owner, hash and salt give the same id
+		assert_noop!(Filesign::create_new_file(Origin::signed(owner), tag.clone(), filehash, None, FileIdMode::Deterministic(salt.clone()), false),
+			RuntimeError::IdAlreadyExists);
+		// another owner gets another id
+		assert_ne!(expected_id, Filesign::compute_file_id(&4, &filehash, &salt));
+		assert_noop!(Filesign::create_new_file(Origin::signed(owner), tag, filehash, None, FileIdMode::Deterministic(vec![0; MAX_ID_SALT_LENGTH + 1]), false),
+			RuntimeError::SaltTooLong);
+	});
+}
+
+#[test]
+fn it_works_find_file_by_hash() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		assert_eq!(None, Filesign::find_file_by_hash(&filehash));
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false));
+		assert_eq!(Some((file_id, 0)), Filesign::find_file_by_hash(&filehash));
+	});
+}
+
+#[test]
+fn it_works_duplicate_hash_policies() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false));
+
+		set_hash_policy(DuplicateHashPolicy::Reject);
+		assert_noop!(Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit([7; 16]), false),
+			RuntimeError::HashAlreadyRegistered);
+
+		set_hash_policy(DuplicateHashPolicy::AllowSameOwner);
+		assert_noop!(Filesign::create_new_file(Origin::signed(2), tag.clone(), filehash, None, FileIdMode::Explicit([7; 16]), false),
+			RuntimeError::HashAlreadyRegistered);
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit([7; 16]), false));
+
+		set_hash_policy(DuplicateHashPolicy::Allow);
+		assert_ok!(Filesign::create_new_file(Origin::signed(2), tag, filehash, None, FileIdMode::Explicit([8; 16]), false));
+
+		// index keeps the first registration
+		assert_eq!(Some((file_id, 0)), Filesign::find_file_by_hash(&filehash));
+		set_hash_policy(DuplicateHashPolicy::Reject);
+	});
+}
+
+#[test]
+fn it_works_anchor_hash() {
+	new_test_ext_with_event().execute_with(|| {
+		let hash = H256::from([0x11; 32]);
+		System::set_block_number(5);
+
+		assert_ok!(Filesign::anchor_hash(Origin::signed(1), hash));
+		assert_eq!(Some(5), Filesign::anchored_at(&hash));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::HashAnchored(1, hash)), last_event().unwrap());
+
+		System::set_block_number(6);
+		assert_noop!(Filesign::anchor_hash(Origin::signed(2), hash), RuntimeError::AlreadyAnchored);
+		assert_eq!(Some(5), Filesign::anchored_at(&hash));
+	});
+}
+
+#[test]
+fn it_works_anchor_batch() {
+	new_test_ext().execute_with(|| {
+		let root = H256::from([0x22; 32]);
+
+		assert_noop!(Filesign::anchor_batch(Origin::signed(1), root, 0), RuntimeError::EmptyBatch);
+		assert_ok!(Filesign::anchor_batch(Origin::signed(1), root, 1000));
+
+		let anchor = Filesign::anchors(root).unwrap();
+		assert_eq!(1, anchor.owner);
+		assert_eq!(1000, anchor.leaves);
+	});
+}
+
+fn generate_leaves(count: u8) -> Vec<H256> {
+	(0..count).map(|i| H256::from([i; 32])).collect()
+}
+
+#[test]
+fn it_works_merkle_inclusion_proofs() {
+	let leaves = generate_leaves(5);
+	let root = merkle_root(&leaves).unwrap();
+
+	for (i, leaf) in leaves.iter().enumerate() {
+		let proof = merkle_proof(&leaves, i).unwrap();
+		assert!(Filesign::verify_inclusion(&root, leaf, &proof));
+		assert!(!Filesign::verify_inclusion(&root, &H256::from([0xff; 32]), &proof));
+	}
+	assert_eq!(None, merkle_proof(&leaves, 5));
+	assert_eq!(None, merkle_root(&[]));
+}
+
+#[test]
+fn it_works_leaf_anchored_in_batch() {
+	new_test_ext().execute_with(|| {
+		let leaves = generate_leaves(7);
+		let root = merkle_root(&leaves).unwrap();
+		let proof = merkle_proof(&leaves, 3).unwrap();
+		System::set_block_number(9);
+
+		assert_eq!(None, Filesign::leaf_anchored_at(&root, &leaves[3], &proof));
+		assert_ok!(Filesign::anchor_batch(Origin::signed(1), root, leaves.len() as u32));
+		assert_eq!(Some(9), Filesign::leaf_anchored_at(&root, &leaves[3], &proof));
+		assert_eq!(None, Filesign::leaf_anchored_at(&root, &leaves[4], &proof));
+	});
+}
+
+#[test]
+fn it_works_verify_file_inclusion() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let leaves = generate_leaves(4);
+		let root = merkle_root(&leaves).unwrap();
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, root, None, FileIdMode::Explicit(file_id), false);
+		let proof = merkle_proof(&leaves, 2).unwrap();
+
+		assert!(Filesign::verify_file_inclusion(file_id, 0, &leaves[2], &proof));
+		assert!(!Filesign::verify_file_inclusion(file_id, 1, &leaves[2], &proof));
+		assert!(!Filesign::verify_file_inclusion(file_id, 0, &leaves[1], &proof));
+	});
+}
+
+#[test]
+fn it_works_sign_latest_version_on_behalf() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let principal = 2;
+		let delegate = 3;
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, principal);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(principal), file_id));
+
+		assert_noop!(Filesign::sign_latest_version_on_behalf(Origin::signed(delegate), file_id, principal),
+			RuntimeError::NotDelegate);
+		assert_ok!(Filesign::add_delegate(Origin::signed(principal), delegate, Some(file_id), 10));
+		assert_ok!(Filesign::sign_latest_version_on_behalf(Origin::signed(delegate), file_id, principal));
+
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		let signature = &file.versions.last().unwrap().signatures[0];
+		assert_eq!(principal, signature.address);
+		assert_eq!(Some(delegate), signature.delegate);
+		assert!(Filesign::address_has_signed_the_file(file_id, &principal));
+		assert!(!Filesign::address_has_signed_the_file(file_id, &delegate));
+		assert!(event_deposited(Event::pallet_filesign(crate::RawEvent::FileSignedByDelegate(delegate, principal, file_id, 0, filehash, tag))));
+	});
+}
+
+#[test]
+fn it_works_global_delegation_expiry_and_removal() {
+	new_test_ext_with_event().execute_with(|| {
+		let principal = 2;
+		let delegate = 3;
+		let file_id = generate_file_id();
+
+		assert_noop!(Filesign::add_delegate(Origin::signed(principal), principal, None, 10), RuntimeError::SelfDelegation);
+		assert_noop!(Filesign::add_delegate(Origin::signed(principal), delegate, None, 1), RuntimeError::InvalidExpiry);
+
+		assert_ok!(Filesign::add_delegate(Origin::signed(principal), delegate, None, 10));
+		assert!(Filesign::address_is_delegate_for_file(file_id, &principal, &delegate));
+		assert!(Filesign::address_is_delegate_for_file([9; 16], &principal, &delegate));
+
+		System::set_block_number(10);
+		assert!(!Filesign::address_is_delegate_for_file(file_id, &principal, &delegate));
+
+		assert_ok!(Filesign::remove_delegate(Origin::signed(principal), delegate, None));
+		assert_noop!(Filesign::remove_delegate(Origin::signed(principal), delegate, None), RuntimeError::DelegationNotFound);
+	});
+}
+
+#[test]
+fn it_works_sign_with_payload() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let signer = 2;
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, signer);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(signer), file_id));
+
+		let signature = TestSignature(signer, Filesign::signing_payload(file_id, 0, &signer));
+		let call = crate::Call::sign_with_payload(file_id, 0, signer, signature.clone());
+		assert_ok!(<Filesign as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call));
+
+		assert_ok!(Filesign::sign_with_payload(Origin::none(), file_id, 0, signer, signature.clone()));
+		assert!(Filesign::address_has_signed_the_file(file_id, &signer));
+		assert_eq!(1, Filesign::payload_nonce(signer));
+		assert!(event_deposited(Event::pallet_filesign(crate::RawEvent::FileSigned(signer, file_id, 0, filehash, tag))));
+
+		// replay of the same payload is stale after the nonce bump
+		assert!(<Filesign as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call).is_err());
+		assert_noop!(Filesign::sign_with_payload(Origin::none(), file_id, 0, signer, signature),
+			RuntimeError::InvalidPayloadSignature);
+	});
+}
+
+#[test]
+fn it_fails_sign_with_payload_bad_signature() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let signer = 2;
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, signer);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(signer), file_id));
+
+		// signed by another key
+		let forged = TestSignature(3, Filesign::signing_payload(file_id, 0, &signer));
+		assert_noop!(Filesign::sign_with_payload(Origin::none(), file_id, 0, signer, forged),
+			RuntimeError::InvalidPayloadSignature);
+		// payload for a version that doesn't exist
+		let wrong_version = TestSignature(signer, Filesign::signing_payload(file_id, 1, &signer));
+		assert_noop!(Filesign::sign_with_payload(Origin::none(), file_id, 1, signer, wrong_version),
+			RuntimeError::InvalidPayloadSignature);
+		// signed origin is not allowed
+		let signature = TestSignature(signer, Filesign::signing_payload(file_id, 0, &signer));
+		assert!(Filesign::sign_with_payload(Origin::signed(signer), file_id, 0, signer, signature).is_err());
+	});
+}
+
+#[test]
+fn it_fails_sign_with_payload_already_signed_or_finalized() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let signer = 2;
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, signer);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(signer), file_id));
+
+		// the signer signs directly, the pre-signed payload can't be included anymore
+		let signature = TestSignature(signer, Filesign::signing_payload(file_id, 0, &signer));
+		let call = crate::Call::sign_with_payload(file_id, 0, signer, signature.clone());
+		assert_ok!(<Filesign as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(signer), file_id));
+		assert!(<Filesign as ValidateUnsigned>::validate_unsigned(TransactionSource::InBlock, &call).is_err());
+		assert_noop!(Filesign::sign_with_payload(Origin::none(), file_id, 0, signer, signature),
+			RuntimeError::InvalidPayloadSignature);
+
+		// payloads for a finalized file are rejected as well
+		let other_id = [7; 16];
+		let _ = Filesign::create_new_file(Origin::signed(1), vec![1], H256::from([0x67; 32]), None, FileIdMode::Explicit(other_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), other_id, signer);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(signer), other_id));
+		let signature = TestSignature(signer, Filesign::signing_payload(other_id, 0, &signer));
+		crate::FinalizedAt::<TestRuntime>::insert(other_id, 1);
+		let call = crate::Call::sign_with_payload(other_id, 0, signer, signature);
+		assert!(<Filesign as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call).is_err());
+	});
+}
+
+#[test]
+fn it_binds_signing_payload_to_genesis_hash() {
+	new_test_ext().execute_with(|| {
+		let file_id = generate_file_id();
+		let payload = Filesign::signing_payload(file_id, 0, &2);
+
+		frame_system::BlockHash::<TestRuntime>::insert(0, sp_core::H256::from([0x11; 32]));
+		assert_ne!(payload, Filesign::signing_payload(file_id, 0, &2));
+	});
+}
+
+#[test]
+fn it_works_group_signers() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10, 11, 12, 11], 2));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::GroupCreated(1, 0)), last_event().unwrap());
+		let group = Filesign::group_by_id(0).unwrap();
+		assert_eq!(vec![10, 11, 12], group.members);
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::assign_group(Origin::signed(1), file_id, 0));
+
+		// members count only after accepting the invitation
+		assert_noop!(Filesign::sign_latest_version(Origin::signed(10), file_id), RuntimeError::AddressNotSigner);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(10), file_id));
+		assert_ok!(Filesign::accept_invitation(Origin::signed(12), file_id));
+		assert_ok!(Filesign::decline_invitation(Origin::signed(11), file_id));
+		let group = Filesign::get_file_by_id(file_id).unwrap().signer_groups[0].clone();
+		assert_eq!(vec![10, 12], group.members);
+		assert!(group.invited.is_empty());
+		assert_noop!(Filesign::accept_invitation(Origin::signed(11), file_id), RuntimeError::NoInvitation);
+
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(10), file_id));
+		assert!(!Filesign::latest_version_is_signed(file_id));
+
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(12), file_id));
+		assert!(Filesign::latest_version_is_signed(file_id));
+
+		assert_noop!(Filesign::sign_latest_version(Origin::signed(13), file_id), RuntimeError::AddressNotSigner);
+	});
+}
+
+#[test]
+fn it_keeps_group_members_of_assigned_files() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		// group owned by another account
+		assert_ok!(Filesign::create_group(Origin::signed(5), b"Board".to_vec(), vec![10, 11], 2));
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::assign_group(Origin::signed(1), file_id, 0));
+		assert_ok!(Filesign::accept_invitation(Origin::signed(10), file_id));
+		assert_ok!(Filesign::accept_invitation(Origin::signed(11), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(10), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(11), file_id));
+		assert!(Filesign::latest_version_is_signed(file_id));
+		assert_ok!(Filesign::finalize_file(Origin::signed(1), file_id));
+
+		// the group owner changes the group, the finalized file is not affected
+		assert_ok!(Filesign::update_group(Origin::signed(5), 0, vec![20, 21, 22], 3));
+		assert!(Filesign::latest_version_is_signed(file_id));
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		assert_eq!(vec![10, 11], file.signer_groups[0].members);
+		assert_eq!(2, file.signer_groups[0].threshold);
+	});
+}
+
+#[test]
+fn it_fails_too_many_groups() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		for group_id in 0..MAX_FILE_GROUPS as u32 {
+			assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10], 1));
+			assert_ok!(Filesign::assign_group(Origin::signed(1), file_id, group_id));
+		}
+		assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10], 1));
+		assert_noop!(Filesign::assign_group(Origin::signed(1), file_id, MAX_FILE_GROUPS as u32), RuntimeError::TooManyGroups);
+	});
+}
+
+#[test]
+fn it_fails_group_validation() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		assert_noop!(Filesign::create_group(Origin::signed(1), Vec::new(), vec![10], 1), RuntimeError::InvalidGroupName);
+		assert_noop!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), Vec::new(), 1), RuntimeError::InvalidGroupMembers);
+		assert_noop!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10, 10], 2), RuntimeError::InvalidThreshold);
+		assert_noop!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10], 0), RuntimeError::InvalidThreshold);
+
+		assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10], 1));
+		assert_noop!(Filesign::update_group(Origin::signed(2), 0, vec![10, 11], 2), RuntimeError::AddressNotGroupOwner);
+		assert_ok!(Filesign::update_group(Origin::signed(1), 0, vec![10, 11], 2));
+		assert_eq!(2, Filesign::group_by_id(0).unwrap().threshold);
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_noop!(Filesign::assign_group(Origin::signed(1), file_id, 5), RuntimeError::GroupNotFound);
+		assert_noop!(Filesign::delete_group(Origin::signed(1), file_id, 0), RuntimeError::GroupNotAssigned);
+		assert_ok!(Filesign::assign_group(Origin::signed(1), file_id, 0));
+		assert_ok!(Filesign::delete_group(Origin::signed(1), file_id, 0));
+		assert!(Filesign::get_file_by_id(file_id).unwrap().signer_groups.is_empty());
+	});
+}
+
+
+#[test]
+fn it_works_invitation_flow() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::SignerInvited(1, file_id, 2)), last_event().unwrap());
+
+		// invited signer doesn't count until accepted
+		assert!(!Filesign::address_is_signer_for_file(file_id, &2));
+		assert_noop!(Filesign::sign_latest_version(Origin::signed(2), file_id), RuntimeError::AddressNotSigner);
+
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::SignerAssigned(1, file_id, 2)), last_event().unwrap());
+		assert!(System::events().iter().any(|record| record.event == Event::pallet_filesign(crate::RawEvent::InvitationAccepted(2, file_id))));
+		assert!(Filesign::address_is_signer_for_file(file_id, &2));
+		assert_noop!(Filesign::accept_invitation(Origin::signed(2), file_id), RuntimeError::NoInvitation);
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+	});
+}
+
+#[test]
+fn it_works_decline_and_cancel_invitation() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 3);
+
+		assert_noop!(Filesign::decline_invitation(Origin::signed(4), file_id), RuntimeError::NoInvitation);
+		assert_ok!(Filesign::decline_invitation(Origin::signed(2), file_id));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::InvitationDeclined(2, file_id)), last_event().unwrap());
+
+		// owner cancels a pending invitation with delete_signer
+		assert_ok!(Filesign::delete_signer(Origin::signed(1), file_id, 3));
+
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		assert!(file.invitations.is_empty());
+		assert!(file.signers.is_empty());
+	});
+}
+
+#[test]
+fn it_works_create_new_version() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let new_filehash = H256::from([0x67; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_noop!(Filesign::create_new_version(Origin::signed(2), file_id, tag.clone(), new_filehash, None, None, false),
+			RuntimeError::AddressNotOwner);
+		assert_ok!(Filesign::create_new_version(Origin::signed(1), file_id, tag.clone(), new_filehash, None, None, false));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::VersionCreated(1, file_id, 1, new_filehash, tag)), last_event().unwrap());
+
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		assert_eq!(2, file.versions.len());
+		assert_eq!(new_filehash, file.versions[1].filehash);
+		assert_eq!(Some((file_id, 1)), Filesign::find_file_by_hash(&new_filehash));
+	});
+}
+
+#[test]
+fn it_works_finalize_file() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		System::set_block_number(3);
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+
+		assert_noop!(Filesign::finalize_file(Origin::signed(1), file_id), RuntimeError::VersionNotApproved);
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_noop!(Filesign::finalize_file(Origin::signed(2), file_id), RuntimeError::AddressNotOwner);
+		assert_ok!(Filesign::finalize_file(Origin::signed(1), file_id));
+
+		assert!(Filesign::file_is_finalized(file_id));
+		assert_eq!(Some(3), Filesign::finalized_at(file_id));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::FileFinalized(1, file_id, 3)), last_event().unwrap());
+	});
+}
+
+#[test]
+fn it_fails_change_finalized_file() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_ok!(Filesign::finalize_file(Origin::signed(1), file_id));
+
+		assert_noop!(Filesign::assign_signer(Origin::signed(1), file_id, 3), RuntimeError::FileFinalized);
+		assert_noop!(Filesign::delete_signer(Origin::signed(1), file_id, 2), RuntimeError::FileFinalized);
+		assert_noop!(Filesign::create_new_version(Origin::signed(1), file_id, tag, H256::from([0x67; 32]), None, None, false),
+			RuntimeError::FileFinalized);
+		assert_noop!(Filesign::finalize_file(Origin::signed(1), file_id), RuntimeError::FileFinalized);
+	});
+}
+
+#[test]
+fn it_works_version_branching() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), H256::from([0x60; 32]), None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::create_new_version(Origin::signed(1), file_id, tag.clone(), H256::from([0x61; 32]), None, None, false));
+		assert_ok!(Filesign::create_new_version(Origin::signed(1), file_id, tag.clone(), H256::from([0x62; 32]), None, None, false));
+		// amendment branching from the first version
+		assert_ok!(Filesign::create_new_version(Origin::signed(1), file_id, tag.clone(), H256::from([0x63; 32]), None, Some(0), false));
+		assert_noop!(Filesign::create_new_version(Origin::signed(1), file_id, tag, H256::from([0x64; 32]), None, Some(4), false),
+			RuntimeError::VersionNotFound);
+
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		assert_eq!(None, file.versions[0].parent);
+		assert_eq!(Some(1), file.versions[2].parent);
+		assert_eq!(vec![2, 1, 0], Filesign::version_lineage(file_id, 2));
+		assert_eq!(vec![3, 0], Filesign::version_lineage(file_id, 3));
+		assert!(Filesign::version_lineage(file_id, 7).is_empty());
+	});
+}
+
+#[test]
+fn it_works_supersede_file() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let (first, second, third) = ([1; 16], [2; 16], [3; 16]);
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), H256::from([0x61; 32]), None, FileIdMode::Explicit(first), false);
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), H256::from([0x62; 32]), None, FileIdMode::Explicit(second), false);
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, H256::from([0x63; 32]), None, FileIdMode::Explicit(third), false);
+
+		assert_noop!(Filesign::supersede_file(Origin::signed(2), first, second), RuntimeError::AddressNotOwner);
+		assert_noop!(Filesign::supersede_file(Origin::signed(1), first, first), RuntimeError::SelfSupersession);
+		assert_ok!(Filesign::supersede_file(Origin::signed(1), first, second));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::Superseded(first, second)), last_event().unwrap());
+		assert_ok!(Filesign::supersede_file(Origin::signed(1), second, third));
+
+		assert_noop!(Filesign::supersede_file(Origin::signed(1), first, third), RuntimeError::AlreadySuperseded);
+		// would close a cycle
+		assert_noop!(Filesign::supersede_file(Origin::signed(1), third, first), RuntimeError::AlreadySuperseded);
+
+		assert_eq!(Some(second), Filesign::superseded_by(first));
+		assert_eq!(vec![third, second, first], Filesign::file_lineage(third));
+	});
+}
+
+#[test]
+fn it_works_create_from_template() {
+	new_test_ext_with_event().execute_with(|| {
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let roles = vec![b"Auditor".to_vec(), b"Issuer".to_vec()];
+
+		assert_ok!(Filesign::create_template(Origin::signed(1), b"Bond report".to_vec(), roles, SigningPolicy::Threshold(1)));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::TemplateCreated(1, 0)), last_event().unwrap());
+
+		assert_ok!(Filesign::create_from_template(Origin::signed(1), 0, filehash, FileIdMode::Explicit(file_id),
+			vec![(b"Issuer".to_vec(), 3), (b"Auditor".to_vec(), 2)], true));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::TemplateInstantiated(1, 0, file_id)), last_event().unwrap());
+
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		assert_eq!(b"Bond report".to_vec(), file.versions[0].tag);
+		assert_eq!(SigningPolicy::Threshold(1), file.policy);
+		assert_eq!(SaltState::Committed, file.versions[0].salt);
+		assert_eq!(vec![3, 2], file.invitations);
+		assert_eq!(Some(b"Auditor".to_vec()), Filesign::signer_role(file_id, 2));
+
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::accept_invitation(Origin::signed(3), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		// threshold policy needs one signature only
+		assert!(Filesign::latest_version_is_signed(file_id));
+	});
+}
+
+#[test]
+fn it_fails_create_from_template_role_mismatch() {
+	new_test_ext().execute_with(|| {
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let roles = vec![b"Auditor".to_vec(), b"Issuer".to_vec()];
+
+		assert_noop!(Filesign::create_template(Origin::signed(1), b"Report".to_vec(), vec![b"Auditor".to_vec(), b"Auditor".to_vec()], SigningPolicy::All),
+			RuntimeError::InvalidRoles);
+		assert_noop!(Filesign::create_template(Origin::signed(1), b"Report".to_vec(), roles.clone(), SigningPolicy::Threshold(3)),
+			RuntimeError::InvalidThreshold);
+		assert_ok!(Filesign::create_template(Origin::signed(1), b"Report".to_vec(), roles, SigningPolicy::All));
+
+		assert_noop!(Filesign::create_from_template(Origin::signed(1), 1, filehash, FileIdMode::Explicit(file_id), Vec::new(), false),
+			RuntimeError::TemplateNotFound);
+		// missing role
+		assert_noop!(Filesign::create_from_template(Origin::signed(1), 0, filehash, FileIdMode::Explicit(file_id),
+			vec![(b"Auditor".to_vec(), 2)], false), RuntimeError::RoleMismatch);
+		// unknown role
+		assert_noop!(Filesign::create_from_template(Origin::signed(1), 0, filehash, FileIdMode::Explicit(file_id),
+			vec![(b"Auditor".to_vec(), 2), (b"Lawyer".to_vec(), 3)], false), RuntimeError::RoleMismatch);
+		// one account in two roles
+		assert_noop!(Filesign::create_from_template(Origin::signed(1), 0, filehash, FileIdMode::Explicit(file_id),
+			vec![(b"Auditor".to_vec(), 2), (b"Issuer".to_vec(), 2)], false), RuntimeError::RoleMismatch);
+	});
+}
+
+#[test]
+fn it_works_signing_hooks() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::create_new_version(Origin::signed(1), file_id, tag, H256::from([0x67; 32]), None, None, false);
+		for signer in [2, 3].iter() {
+			let _ = Filesign::assign_signer(Origin::signed(1), file_id, *signer);
+			assert_ok!(Filesign::accept_invitation(Origin::signed(*signer), file_id));
+		}
+
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_eq!(vec![(file_id, 1, 2)], signed_files());
+		assert!(approved_versions().is_empty());
+
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(3), file_id));
+		assert_eq!(vec![(file_id, 1, 2), (file_id, 1, 3)], signed_files());
+		assert_eq!(vec![(file_id, 1)], approved_versions());
+	});
+}
+
+#[test]
+fn it_works_approval_hooks_on_signer_and_group_removal() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		for signer in [2, 3].iter() {
+			let _ = Filesign::assign_signer(Origin::signed(1), file_id, *signer);
+			assert_ok!(Filesign::accept_invitation(Origin::signed(*signer), file_id));
+		}
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert!(approved_versions().is_empty());
+
+		// removing the last unsigned signer approves the version
+		assert_ok!(Filesign::delete_signer(Origin::signed(1), file_id, 3));
+		assert_eq!(vec![(file_id, 0)], approved_versions());
+		assert!(event_deposited(Event::pallet_filesign(crate::RawEvent::SignatureCompleted(file_id, 0, filehash))));
+
+		// same through a governance removal
+		let filehash = H256::from([0x67; 32]);
+		let _ = Filesign::create_new_version(Origin::signed(1), file_id, tag.clone(), filehash, None, None, false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 3);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(3), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_ok!(Filesign::force_remove_signer(Origin::root(), file_id, 3));
+		assert_eq!(vec![(file_id, 0), (file_id, 1)], approved_versions());
+		assert!(event_deposited(Event::pallet_filesign(crate::RawEvent::SignatureCompleted(file_id, 1, filehash))));
+
+		// and when an unsigned group is removed
+		let filehash = H256::from([0x68; 32]);
+		let _ = Filesign::create_new_version(Origin::signed(1), file_id, tag, filehash, None, None, false);
+		assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10], 1));
+		assert_ok!(Filesign::assign_group(Origin::signed(1), file_id, 0));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_eq!(2, approved_versions().len());
+		assert_ok!(Filesign::delete_group(Origin::signed(1), file_id, 0));
+		assert_eq!(vec![(file_id, 0), (file_id, 1), (file_id, 2)], approved_versions());
+		assert!(event_deposited(Event::pallet_filesign(crate::RawEvent::SignatureCompleted(file_id, 2, filehash))));
+	});
+}
+
+#[test]
+fn it_works_force_transfer_owner() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_noop!(Filesign::force_transfer_owner(Origin::signed(1), file_id, 2), BadOrigin);
+		assert_noop!(Filesign::force_transfer_owner(Origin::root(), [9; 16], 2), RuntimeError::FileNotFound);
+		assert_ok!(Filesign::force_transfer_owner(Origin::root(), file_id, 2));
+
+		assert!(Filesign::address_is_owner_for_file(file_id, &2));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::OwnerForceTransferred(file_id, 1, 2)), last_event().unwrap());
+	});
+}
+
+#[test]
+fn it_works_force_remove_signer() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+
+		assert_noop!(Filesign::force_remove_signer(Origin::signed(1), file_id, 2), BadOrigin);
+		assert_ok!(Filesign::force_remove_signer(Origin::root(), file_id, 2));
+		assert!(!Filesign::address_is_signer_for_file(file_id, &2));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::SignerForceRemoved(file_id, 2)), last_event().unwrap());
+		assert_noop!(Filesign::force_remove_signer(Origin::root(), file_id, 2), RuntimeError::AddressNotSigner);
+	});
+}
+
+#[test]
+fn it_fails_force_remove_signer_finalized_file() {
+	new_test_ext().execute_with(|| {
+		let file_id = generate_file_id();
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), None, FileIdMode::Explicit(file_id), false));
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_ok!(Filesign::finalize_file(Origin::signed(1), file_id));
+
+		// approval state of a finalized file doesn't change
+		assert_noop!(Filesign::force_remove_signer(Origin::root(), file_id, 2), RuntimeError::FileFinalized);
+		assert!(Filesign::address_is_signer_for_file(file_id, &2));
+	});
+}
+
+#[test]
+fn it_works_force_delete_file() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+		let new_file_id = [7; 16];
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, H256::from([0x67; 32]), None, FileIdMode::Explicit(new_file_id), false);
+		assert_ok!(Filesign::supersede_file(Origin::signed(1), file_id, new_file_id));
+
+		assert_eq!(1, Filesign::file_entries(file_id));
+		assert_noop!(Filesign::force_delete_file(Origin::signed(1), file_id, 1), BadOrigin);
+		assert_noop!(Filesign::force_delete_file(Origin::root(), file_id, 0), RuntimeError::EntriesUnderestimated);
+		assert_ok!(Filesign::force_delete_file(Origin::root(), file_id, 1));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::FileForceDeleted(file_id)), last_event().unwrap());
+
+		assert!(Filesign::get_file_by_id(file_id).is_none());
+		assert_eq!(None, Filesign::find_file_by_hash(&filehash));
+		assert_eq!(vec![new_file_id], Filesign::file_lineage(new_file_id));
+		assert_noop!(Filesign::force_delete_file(Origin::root(), file_id, 1), RuntimeError::FileNotFound);
+	});
+}
+
+#[test]
+fn it_works_signing_events() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), tag.clone(), filehash, None, FileIdMode::Explicit(file_id), false));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::FileCreated(1, file_id, filehash, tag.clone())), last_event().unwrap());
+		for signer in [2, 3].iter() {
+			let _ = Filesign::assign_signer(Origin::signed(1), file_id, *signer);
+			assert_ok!(Filesign::accept_invitation(Origin::signed(*signer), file_id));
+		}
+
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::FileSigned(2, file_id, 0, filehash, tag.clone())), last_event().unwrap());
+
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(3), file_id));
+		let events = System::events();
+		let len = events.len();
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::FileSigned(3, file_id, 0, filehash, tag.clone())), events[len - 2].event);
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::SignatureCompleted(file_id, 0, filehash)), events[len - 1].event);
+	});
+}
+
+#[test]
+fn it_fails_sign_latest_version_twice() {
+	new_test_ext_with_event().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		let events_count = System::events().len();
+
+		assert_noop!(Filesign::sign_latest_version(Origin::signed(2), file_id), RuntimeError::AlreadySigned);
+		assert_eq!(events_count, System::events().len());
+		assert_eq!(vec![(file_id, 0, 2)], signed_files());
+	});
+}
+
+#[test]
+fn it_fails_assign_signer_twice() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+		assert_noop!(Filesign::assign_signer(Origin::signed(1), file_id, 2), RuntimeError::AlreadyAssigned);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_noop!(Filesign::assign_signer(Origin::signed(1), file_id, 2), RuntimeError::AlreadyAssigned);
+
+		assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![10], 1));
+		assert_ok!(Filesign::assign_group(Origin::signed(1), file_id, 0));
+		assert_noop!(Filesign::assign_group(Origin::signed(1), file_id, 0), RuntimeError::GroupAlreadyAssigned);
+	});
+}
+
+#[test]
+fn it_fails_assign_too_many_signers() {
+	new_test_ext().execute_with(|| {
+		let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+		let filehash = H256::from([0x66; 32]);
+		let file_id = generate_file_id();
+
+		let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+		for signer in 2..65 {
+			assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, signer));
+		}
+		// invited group members count as signers
+		assert_ok!(Filesign::create_group(Origin::signed(1), b"Board".to_vec(), vec![100, 101], 1));
+		assert_noop!(Filesign::assign_group(Origin::signed(1), file_id, 0), RuntimeError::TooManySigners);
+
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 65));
+		assert_noop!(Filesign::assign_signer(Origin::signed(1), file_id, 66), RuntimeError::TooManySigners);
+	});
+}
+
+#[test]
+fn it_works_file_struct_typed_errors() {
+	let mut file = FileStruct::<u64>::new(1, generate_file_id(), vec![1], &H256::from([0x66; 32]), None);
+
+	assert_eq!(Err(FileError::AddressNotSigner), file.delete_signer_from_file(2));
+	assert_eq!(Err(FileError::NoInvitation), file.accept_invitation(2));
+	assert_eq!(Ok(()), file.sign_latest_version(2, None));
+	assert_eq!(Err(FileError::AlreadySigned), file.sign_latest_version(2, None));
+	assert_eq!(Err(FileError::AttributeNotFound), file.clear_attribute(b"doctype"));
+
+	file.versions.clear();
+	assert_eq!(Err(FileError::NoVersions), file.sign_latest_version(2, None));
+}
+
+// Creates a file with signer 2 who has signed the first version, returns file id
+fn create_file_signed_by_removable_signer() -> crate::file::FileId {
+	let tag = vec![40, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+	let filehash = H256::from([0x66; 32]);
+	let file_id = generate_file_id();
+
+	let _ = Filesign::create_new_file(Origin::signed(1), tag, filehash, None, FileIdMode::Explicit(file_id), false);
+	let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+	assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+	assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+	assert!(Filesign::address_has_signed_the_file(file_id, &2));
+	file_id
+}
+
+#[test]
+fn it_works_delete_signed_signer_invalidate() {
+	new_test_ext().execute_with(|| {
+		set_removal_policy(RemovedSignerPolicy::Invalidate);
+		let file_id = create_file_signed_by_removable_signer();
+
+		assert_ok!(Filesign::delete_signer(Origin::signed(1), file_id, 2));
+		assert!(!Filesign::address_has_signed_the_file(file_id, &2));
+		let signature = &Filesign::get_file_by_id(file_id).unwrap().versions[0].signatures[0];
+		assert_eq!(2, signature.address);
+		assert!(!signature.signed);
+
+		// signer can sign again after being assigned again
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 2);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert!(Filesign::address_has_signed_the_file(file_id, &2));
+	});
+}
+
+#[test]
+fn it_works_delete_signed_signer_keep_historical() {
+	new_test_ext().execute_with(|| {
+		set_removal_policy(RemovedSignerPolicy::KeepHistorical);
+		let file_id = create_file_signed_by_removable_signer();
+
+		assert_ok!(Filesign::delete_signer(Origin::signed(1), file_id, 2));
+		assert!(!Filesign::address_has_signed_the_file(file_id, &2));
+		let signature = &Filesign::get_file_by_id(file_id).unwrap().versions[0].signatures[0];
+		assert_eq!(2, signature.address);
+		assert!(signature.signed);
+	});
+}
+
+#[test]
+fn it_fails_delete_signed_signer_block_removal() {
+	new_test_ext().execute_with(|| {
+		set_removal_policy(RemovedSignerPolicy::BlockRemoval);
+		let file_id = create_file_signed_by_removable_signer();
+
+		assert_noop!(Filesign::delete_signer(Origin::signed(1), file_id, 2), RuntimeError::SignerHasSigned);
+		assert!(Filesign::address_has_signed_the_file(file_id, &2));
+
+		// signer who hasn't signed yet can be removed
+		let _ = Filesign::assign_signer(Origin::signed(1), file_id, 3);
+		assert_ok!(Filesign::accept_invitation(Origin::signed(3), file_id));
+		assert_ok!(Filesign::delete_signer(Origin::signed(1), file_id, 3));
+
+		// governance override is not blocked
+		assert_ok!(Filesign::force_remove_signer(Origin::root(), file_id, 2));
+		assert!(!Filesign::address_has_signed_the_file(file_id, &2));
+	});
+}
+
+#[test]
+fn it_works_genesis_files() {
+	let file_id = generate_file_id();
+	let tag = vec![50, 1, 2, 3];
+	ExtBuilder::default()
+		.with_file((file_id, 1, tag.clone(), [0x77; 32], vec![2, 3], vec![2]))
+		.build()
+		.execute_with(|| {
+			let file = Filesign::get_file_by_id(file_id).unwrap();
+			assert_eq!(1, file.owner);
+			assert_eq!(tag, file.versions[0].tag);
+			assert_eq!(vec![2, 3], file.signers);
+			assert!(Filesign::address_has_signed_the_file(file_id, &2));
+			assert!(!Filesign::address_has_signed_the_file(file_id, &3));
+			assert!(!Filesign::latest_version_is_signed(file_id));
+			assert_eq!(Some((file_id, 0)), Filesign::find_file_by_hash(&H256::from([0x77; 32])));
+
+			// genesis file works like any other one
+			assert_ok!(Filesign::sign_latest_version(Origin::signed(3), file_id));
+			assert!(Filesign::latest_version_is_signed(file_id));
+		});
+}
+
+#[test]
+#[should_panic(expected = "genesis signature of an address that is not a signer")]
+fn it_fails_genesis_signature_of_not_signer() {
+	ExtBuilder::default()
+		.with_file((generate_file_id(), 1, vec![50, 1], [0x77; 32], vec![2], vec![3]))
+		.build();
+}
+
+// Creates a file with a random id and returns the id from the event
+fn create_random_file(owner: u64, filehash: H256) -> crate::file::FileId {
+	assert_ok!(Filesign::create_new_file(Origin::signed(owner), vec![40, 1], filehash, None, FileIdMode::Random, false));
+	match last_event().unwrap() {
+		Event::pallet_filesign(crate::RawEvent::FileCreated(_, id, _, _)) => id,
+		_ => panic!("event not right")
+	}
+}
+
+#[test]
+fn it_works_random_id_mixes_caller_and_hash() {
+	// same nonce and randomness in every fresh externality
+	let first = new_test_ext_with_event().execute_with(|| create_random_file(3, H256::from([0x66; 32])));
+	let same = new_test_ext_with_event().execute_with(|| create_random_file(3, H256::from([0x66; 32])));
+	let other_caller = new_test_ext_with_event().execute_with(|| create_random_file(4, H256::from([0x66; 32])));
+	let other_hash = new_test_ext_with_event().execute_with(|| create_random_file(3, H256::from([0x67; 32])));
+
+	assert_eq!(first, same);
+	assert_ne!(first, other_caller);
+	assert_ne!(first, other_hash);
+}
+
+#[test]
+fn it_works_random_id_retries_on_collision() {
+	let filehash = H256::from([0x66; 32]);
+	let taken_id = new_test_ext_with_event().execute_with(|| create_random_file(3, filehash));
+
+	new_test_ext_with_event().execute_with(|| {
+		// squat the id the first attempt generates
+		assert_ok!(Filesign::create_new_file(Origin::signed(4), vec![40, 1], H256::from([0x67; 32]), None, FileIdMode::Explicit(taken_id), false));
+
+		let file_id = create_random_file(3, filehash);
+		assert_ne!(taken_id, file_id);
+		assert_eq!(3, Filesign::get_file_by_id(file_id).unwrap().owner);
+		assert_eq!(4, Filesign::get_file_by_id(taken_id).unwrap().owner);
+	});
+}
+
+#[test]
+fn it_works_committed_file_id() {
+	new_test_ext_with_event().execute_with(|| {
+		let secret = b"not guessable".to_vec();
+		let commitment = H256::from(sp_io::hashing::blake2_256(&secret));
+		let filehash = H256::from([0x66; 32]);
+
+		assert_noop!(Filesign::create_new_file(Origin::signed(3), vec![40, 1], filehash, None, FileIdMode::Committed(secret.clone()), false),
+			RuntimeError::IdCommitmentNotFound);
+		assert_ok!(Filesign::commit_file_id(Origin::signed(3), commitment));
+		let file_id: crate::file::FileId = (3u64, commitment, 0u32).using_encoded(sp_io::hashing::blake2_128);
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::FileIdCommitted(3, commitment, file_id)), last_event().unwrap());
+		assert_eq!(Some(3), Filesign::reserved_id(file_id));
+		assert_noop!(Filesign::commit_file_id(Origin::signed(3), commitment), RuntimeError::IdCommitmentExists);
+
+		// the reserved id can't be squatted in front of the reveal
+		assert_noop!(Filesign::create_new_file(Origin::signed(4), vec![40, 1], H256::from([0x67; 32]), None, FileIdMode::Explicit(file_id), false),
+			RuntimeError::IdReserved);
+
+		// reveal in the block of the commitment is rejected
+		assert_noop!(Filesign::create_new_file(Origin::signed(3), vec![40, 1], filehash, None, FileIdMode::Committed(secret.clone()), false),
+			RuntimeError::IdRevealTooEarly);
+		System::set_block_number(2);
+		// secret committed by another account
+		assert_noop!(Filesign::create_new_file(Origin::signed(4), vec![40, 1], filehash, None, FileIdMode::Committed(secret.clone()), false),
+			RuntimeError::IdCommitmentNotFound);
+		assert_noop!(Filesign::create_new_file(Origin::signed(3), vec![40, 1], filehash, None, FileIdMode::Committed(Vec::new()), false),
+			RuntimeError::InvalidIdSecret);
+
+		assert_ok!(Filesign::create_new_file(Origin::signed(3), vec![40, 1], filehash, None, FileIdMode::Committed(secret.clone()), false));
+		assert_eq!(3, Filesign::get_file_by_id(file_id).unwrap().owner);
+		assert!(Filesign::reserved_id(file_id).is_none());
+
+		// an id taken in front of the commitment is skipped
+		let other_secret = b"also not guessable".to_vec();
+		let other_commitment = H256::from(sp_io::hashing::blake2_256(&other_secret));
+		let squatted: crate::file::FileId = (3u64, other_commitment, 0u32).using_encoded(sp_io::hashing::blake2_128);
+		assert_ok!(Filesign::create_new_file(Origin::signed(4), vec![40, 1], H256::from([0x69; 32]), None, FileIdMode::Explicit(squatted), false));
+		assert_ok!(Filesign::commit_file_id(Origin::signed(3), other_commitment));
+		let next: crate::file::FileId = (3u64, other_commitment, 1u32).using_encoded(sp_io::hashing::blake2_128);
+		assert_eq!(Some(3), Filesign::reserved_id(next));
+
+		// commitment is consumed
+		assert!(Filesign::id_commitment(3, commitment).is_none());
+		assert_noop!(Filesign::create_new_file(Origin::signed(3), vec![40, 1], H256::from([0x68; 32]), None, FileIdMode::Committed(secret), false),
+			RuntimeError::IdCommitmentNotFound);
+	});
+}
+
+// Notifications the given batch of the block has handed to the off-chain worker
+fn indexed_notifications(block: u64, batch: u32) -> Option<Vec<Notification<u64, u64>>> {
+	StorageValueRef::persistent(&Filesign::notifications_key(block, batch)).get().flatten()
+}
+
+#[test]
+fn it_works_notifications_indexed_off_chain() {
+	let (mut ext, _state, _pool_state) = new_test_ext_with_offchain();
+	let file_id = generate_file_id();
+	ext.execute_with(|| {
+		let _ = Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+		assert_ok!(Filesign::add_delegate(Origin::signed(2), 3, None, 5));
+		<Filesign as OnFinalize<u64>>::on_finalize(1);
+	});
+	ext.persist_offchain_overlay();
+	ext.execute_with(|| {
+		assert_eq!(Some(vec![Notification::SignerInvited(file_id, 2)]), indexed_notifications(1, 0));
+
+		// blocks without expiries index nothing
+		<Filesign as OnInitialize<u64>>::on_initialize(2);
+		System::set_block_number(5);
+		<Filesign as OnInitialize<u64>>::on_initialize(5);
+		assert!(Filesign::delegation_expiries(5).is_empty());
+		// notifications of the first extrinsic don't overwrite the expiries of on_initialize
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 4));
+	});
+	ext.persist_offchain_overlay();
+	ext.execute_with(|| {
+		assert_eq!(None, indexed_notifications(2, 0));
+		assert_eq!(Some(vec![Notification::DelegationExpired(2, 3, None, 5)]), indexed_notifications(5, 0));
+		assert_eq!(Some(vec![Notification::SignerInvited(file_id, 4)]), indexed_notifications(5, 1));
+	});
+}
+
+#[test]
+fn it_fails_too_many_expiries_in_block() {
+	new_test_ext().execute_with(|| {
+		for delegate in 0..crate::MAX_EXPIRIES_PER_BLOCK as u64 {
+			assert_ok!(Filesign::add_delegate(Origin::signed(1), 100 + delegate, None, 5));
+		}
+		assert_noop!(Filesign::add_delegate(Origin::signed(1), 2, None, 5), RuntimeError::TooManyExpiries);
+		// renewal with the same expiry doesn't add an entry
+		assert_ok!(Filesign::add_delegate(Origin::signed(1), 100, None, 5));
+		assert_ok!(Filesign::add_delegate(Origin::signed(1), 2, None, 6));
+	});
+}
+
+#[test]
+fn it_works_no_notification_for_removed_delegation() {
+	let (mut ext, _state, _pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		assert_ok!(Filesign::add_delegate(Origin::signed(2), 3, None, 5));
+		assert_ok!(Filesign::remove_delegate(Origin::signed(2), 3, None));
+
+		System::set_block_number(5);
+		<Filesign as OnInitialize<u64>>::on_initialize(5);
+	});
+	ext.persist_offchain_overlay();
+	ext.execute_with(|| assert_eq!(None, indexed_notifications(5, 0)));
+}
+
+#[test]
+fn it_works_single_expiry_entry_for_renewed_delegation() {
+	let (mut ext, _state, _pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		assert_ok!(Filesign::add_delegate(Origin::signed(2), 3, None, 5));
+		assert_ok!(Filesign::add_delegate(Origin::signed(2), 3, None, 5));
+		assert_eq!(1, Filesign::delegation_expiries(5).len());
+
+		System::set_block_number(5);
+		<Filesign as OnInitialize<u64>>::on_initialize(5);
+	});
+	ext.persist_offchain_overlay();
+	ext.execute_with(|| {
+		assert_eq!(Some(vec![Notification::DelegationExpired(2, 3, None, 5)]), indexed_notifications(5, 0));
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_posts_notifications() {
+	let (mut ext, state, _pool_state) = new_test_ext_with_offchain();
+	let file_id = generate_file_id();
+	let body = format!("{{\"event\":\"SignerInvited\",\"file\":\"0x{}\",\"signer\":\"0x0200000000000000\"}}", "06".repeat(16));
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "http://localhost:8080/notify".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: body.into_bytes(),
+		response: Some(vec![]),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		StorageValueRef::persistent(crate::WEBHOOK_URL_KEY).set(&b"http://localhost:8080/notify".to_vec());
+		let _ = Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+	});
+	ext.persist_offchain_overlay();
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(1);
+		// sent notifications are removed, a second run posts nothing
+		assert_eq!(None, indexed_notifications(1, 0));
+		<Filesign as OffchainWorker<u64>>::offchain_worker(1);
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_without_webhook() {
+	// any request would fail the test as none is expected
+	let (mut ext, _state, _pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		let file_id = generate_file_id();
+		let _ = Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), None, FileIdMode::Explicit(file_id), false);
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+	});
+	ext.persist_offchain_overlay();
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(1);
+		assert!(indexed_notifications(1, 0).is_some());
+	});
+}
+
+// Creates a file with the document behind the location and expects the worker to download it
+fn create_file_with_remote_document(state: &parking_lot::RwLock<sp_core::offchain::testing::OffchainState>,
+	location: FileLocation, uri: &str, filehash: H256, document: &[u8]) -> crate::file::FileId {
+	let file_id = generate_file_id();
+	state.write().expect_request(PendingRequest {
+		method: "GET".into(),
+		uri: uri.into(),
+		response: Some(document.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+	assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], filehash, Some(location), FileIdMode::Explicit(file_id), false));
+	file_id
+}
+
+// Registers the hash checker 7 and puts its key into the keystore of the worker
+fn register_local_hash_checker() {
+	assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![7]));
+	UintAuthorityId::set_all_keys(vec![7u64]);
+}
+
+// Allows the worker to download URL locations of the host
+fn allow_fetch_host(host: &[u8]) {
+	StorageValueRef::persistent(crate::FETCH_HOSTS_KEY).set(&vec![host.to_vec()]);
+}
+
+fn hash_check_cursor() -> Option<(u64, u32)> {
+	StorageValueRef::persistent(crate::HASH_CHECK_CURSOR_KEY).get::<(u64, u32)>().flatten()
+}
+
+fn mismatch_payload(checker: u64, file_id: crate::file::FileId, version: u32) -> crate::MismatchPayload<UintAuthorityId, u64> {
+	crate::MismatchPayload { id: file_id, version, block_number: 1, public: UintAuthorityId(checker) }
+}
+
+#[test]
+fn it_works_offchain_worker_reports_mismatch() {
+	let (mut ext, state, pool_state) = new_test_ext_with_offchain();
+	let file_id = ext.execute_with(|| {
+		register_local_hash_checker();
+		allow_fetch_host(b"example.com");
+		let uri = "https://example.com/contract.pdf";
+		create_file_with_remote_document(&state, FileLocation::Url(uri.as_bytes().to_vec()), uri,
+			H256::from(sp_io::hashing::sha2_256(b"contract")), b"tampered contract")
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(None, tx.signature);
+		match tx.call {
+			Call::Filesign(crate::Call::report_mismatch(payload, signature)) => {
+				assert_eq!(crate::MismatchPayload { id: file_id, version: 0, block_number: 10, public: UintAuthorityId(7) }, payload);
+				assert!(<crate::MismatchPayload<UintAuthorityId, u64> as SignedPayload<TestRuntime>>::verify::<TestAuthId>(&payload, signature));
+			}
+			call => panic!("unexpected call {:?}", call),
+		}
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_without_hash_checker_key() {
+	// any request would fail the test as none is expected
+	let (mut ext, _state, pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		let location = FileLocation::Url(b"https://example.com/contract.pdf".to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), Some(location), FileIdMode::Explicit(generate_file_id()), false));
+
+		// the local key is not a registered checker
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![8]));
+		UintAuthorityId::set_all_keys(vec![7u64]);
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_matching_ipfs_document() {
+	let (mut ext, state, pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		register_local_hash_checker();
+		// the gateway is chosen by the node operator and needs no allowed host
+		StorageValueRef::persistent(crate::IPFS_GATEWAY_KEY).set(&b"http://localhost:8080/ipfs/".to_vec());
+		let cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+		create_file_with_remote_document(&state, FileLocation::Ipfs(cid.as_bytes().to_vec()), &format!("http://localhost:8080/ipfs/{}", cid),
+			H256::from(sp_io::hashing::sha2_256(b"contract")), b"contract");
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		// not a check block, no request
+		<Filesign as OffchainWorker<u64>>::offchain_worker(9);
+		assert_eq!(None, hash_check_cursor());
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(Some((1, 0)), hash_check_cursor());
+		// checked version is not downloaded again
+		<Filesign as OffchainWorker<u64>>::offchain_worker(20);
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_skips_host_not_allowed() {
+	// any request would fail the test as none is expected
+	let (mut ext, _state, pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		register_local_hash_checker();
+		allow_fetch_host(b"example.com");
+		for (i, url) in [&b"http://localhost:8080/contract.pdf"[..], b"https://example.com@localhost/contract.pdf"].iter().enumerate() {
+			let location = FileLocation::Url(url.to_vec());
+			assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([i as u8; 32]), Some(location), FileIdMode::Explicit([i as u8; 16]), false));
+		}
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(Some((2, 0)), hash_check_cursor());
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_limits_checks_per_run() {
+	let (mut ext, _state, _pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		register_local_hash_checker();
+		for i in 0..crate::MAX_HASH_CHECKS_PER_RUN + 1 {
+			let location = FileLocation::S3(b"bucket/contract.pdf".to_vec());
+			assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([i as u8; 32]), Some(location), FileIdMode::Explicit([i as u8; 16]), false));
+		}
+		// versions without a location are not queued
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0xff; 32]), None, FileIdMode::Explicit([0xff; 16]), false));
+		assert_eq!(crate::MAX_HASH_CHECKS_PER_RUN + 1, Filesign::hash_check_count());
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+		assert_eq!(Some((crate::MAX_HASH_CHECKS_PER_RUN, 0)), hash_check_cursor());
+		// checked entries are removed from the queue
+		let first = StorageValueRef::persistent(&(crate::HASH_CHECK_KEY_PREFIX, 0u64).encode()).get::<(crate::file::FileId, u32)>();
+		assert_eq!(None, first.flatten());
+
+		<Filesign as OffchainWorker<u64>>::offchain_worker(20);
+		assert_eq!(Some((crate::MAX_HASH_CHECKS_PER_RUN + 1, 0)), hash_check_cursor());
+	});
+}
+
+#[test]
+fn it_works_report_mismatch() {
+	new_test_ext_with_event().execute_with(|| {
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![7]));
+		let file_id = generate_file_id();
+		let location = FileLocation::Url(b"https://example.com/contract.pdf".to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), Some(location), FileIdMode::Explicit(file_id), false));
+		let payload = mismatch_payload(7, file_id, 0);
+		let signature = TestSignature(7, payload.encode());
+		let call = crate::Call::report_mismatch(payload.clone(), signature.clone());
+
+		// valid for every source, the signature is the proof
+		assert!(Filesign::validate_unsigned(TransactionSource::External, &call).is_ok());
+		assert!(Filesign::validate_unsigned(TransactionSource::InBlock, &call).is_ok());
+		assert_noop!(Filesign::report_mismatch(Origin::signed(1), payload.clone(), signature.clone()), BadOrigin);
+		assert_noop!(Filesign::report_mismatch(Origin::none(), mismatch_payload(7, file_id, 1), signature.clone()), RuntimeError::VersionNotFound);
+
+		assert_ok!(Filesign::report_mismatch(Origin::none(), payload.clone(), signature.clone()));
+		assert_eq!(Some(1), Filesign::mismatches(file_id, 0));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::MismatchReported(7, file_id, 0)), last_event().unwrap());
+
+		assert!(Filesign::validate_unsigned(TransactionSource::InBlock, &call).is_err());
+		assert_noop!(Filesign::report_mismatch(Origin::none(), payload, signature), RuntimeError::MismatchAlreadyReported);
+
+		// governance clears a wrong report
+		assert_noop!(Filesign::clear_mismatch(Origin::signed(1), file_id, 0), BadOrigin);
+		assert_ok!(Filesign::clear_mismatch(Origin::root(), file_id, 0));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::MismatchCleared(file_id, 0)), last_event().unwrap());
+		assert_eq!(None, Filesign::mismatches(file_id, 0));
+		assert_noop!(Filesign::clear_mismatch(Origin::root(), file_id, 0), RuntimeError::MismatchNotFound);
+	});
+}
+
+#[test]
+fn it_fails_report_mismatch_replay() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![7]));
+		let file_id = generate_file_id();
+		let location = FileLocation::Url(b"https://example.com/contract.pdf".to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), Some(location), FileIdMode::Explicit(file_id), false));
+		let payload = mismatch_payload(7, file_id, 0);
+		let signature = TestSignature(7, payload.encode());
+		assert_ok!(Filesign::report_mismatch(Origin::none(), payload.clone(), signature.clone()));
+		assert_ok!(Filesign::clear_mismatch(Origin::root(), file_id, 0));
+		assert_eq!(Some(1), Filesign::mismatch_cleared_at(file_id, 0));
+
+		// the report signed before the clear can't flag the file again
+		let call = crate::Call::report_mismatch(payload.clone(), signature.clone());
+		assert!(Filesign::validate_unsigned(TransactionSource::External, &call).is_err());
+		assert_noop!(Filesign::report_mismatch(Origin::none(), payload, signature), RuntimeError::MismatchReportOutdated);
+
+		// a report signed later is accepted until it gets too old
+		System::set_block_number(2);
+		let payload = crate::MismatchPayload { block_number: 2, ..mismatch_payload(7, file_id, 0) };
+		let signature = TestSignature(7, payload.encode());
+		System::set_block_number(2 + crate::MISMATCH_REPORT_LONGEVITY as u64 + 1);
+		assert_noop!(Filesign::report_mismatch(Origin::none(), payload.clone(), signature.clone()), RuntimeError::MismatchReportOutdated);
+		System::set_block_number(2 + crate::MISMATCH_REPORT_LONGEVITY as u64);
+		assert_ok!(Filesign::report_mismatch(Origin::none(), payload, signature));
+	});
+}
+
+#[test]
+fn it_fails_report_mismatch_without_checker_signature() {
+	new_test_ext_with_event().execute_with(|| {
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![7]));
+		let file_id = generate_file_id();
+		let location = FileLocation::Url(b"https://example.com/contract.pdf".to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), Some(location), FileIdMode::Explicit(file_id), false));
+
+		// made-up report put into a block by its author
+		let payload = mismatch_payload(7, file_id, 0);
+		let forged = crate::Call::report_mismatch(payload.clone(), TestSignature(8, payload.encode()));
+		assert!(Filesign::validate_unsigned(TransactionSource::InBlock, &forged).is_err());
+
+		// properly signed by a key which is not a checker
+		let payload = mismatch_payload(8, file_id, 0);
+		let call = crate::Call::report_mismatch(payload.clone(), TestSignature(8, payload.encode()));
+		assert!(Filesign::validate_unsigned(TransactionSource::InBlock, &call).is_err());
+		assert_noop!(Filesign::report_mismatch(Origin::none(), payload.clone(), TestSignature(8, payload.encode())), RuntimeError::NotHashChecker);
+	});
+}
+
+#[test]
+fn it_fails_report_mismatch_no_location() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![7]));
+		let file_id = generate_file_id();
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), None, FileIdMode::Explicit(file_id), false));
+		let payload = mismatch_payload(7, file_id, 0);
+		assert_noop!(Filesign::report_mismatch(Origin::none(), payload.clone(), TestSignature(7, payload.encode())), RuntimeError::NoLocation);
+	});
+}
+
+#[test]
+fn it_works_set_hash_checkers() {
+	new_test_ext_with_event().execute_with(|| {
+		assert_noop!(Filesign::set_hash_checkers(Origin::signed(1), vec![7]), BadOrigin);
+		assert_noop!(Filesign::set_hash_checkers(Origin::root(), (0..crate::MAX_HASH_CHECKERS as u64 + 1).collect()),
+			RuntimeError::TooManyHashCheckers);
+
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![8, 7, 8]));
+		assert_eq!(vec![7, 8], Filesign::hash_checkers());
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::HashCheckersSet(vec![7, 8])), last_event().unwrap());
+	});
+}
+
+#[test]
+fn it_works_create_private_file() {
+	new_test_ext().execute_with(|| {
+		let file_id = generate_file_id();
+		let commitment = Filesign::tag_commitment(b"Supply contract with ACME", b"secret salt");
+		assert_ok!(Filesign::create_private_file(Origin::signed(1), commitment, H256::from([0x66; 32]), FileIdMode::Explicit(file_id), false));
+
+		let file = Filesign::get_file_by_id(file_id).unwrap();
+		assert!(file.private);
+		assert_eq!(SaltState::Unsalted, file.versions[0].salt);
+		assert_eq!(commitment.as_bytes().to_vec(), file.versions[0].tag);
+		assert!(Filesign::verify_tag(file_id, 0, b"Supply contract with ACME", b"secret salt"));
+		assert!(!Filesign::verify_tag(file_id, 0, b"Supply contract with ACME", b"wrong salt"));
+		assert!(!Filesign::verify_tag(file_id, 1, b"Supply contract with ACME", b"secret salt"));
+
+		// versions of a private file carry commitments too
+		assert_noop!(Filesign::create_new_version(Origin::signed(1), file_id, vec![40, 1], H256::from([0x67; 32]), None, None, false),
+			RuntimeError::InvalidCommitment);
+		let commitment = Filesign::tag_commitment(b"Supply contract with ACME v2", b"secret salt");
+		assert_ok!(Filesign::create_new_version(Origin::signed(1), file_id, commitment.as_bytes().to_vec(), H256::from([0x67; 32]), None, None, false));
+		assert!(Filesign::verify_tag(file_id, 1, b"Supply contract with ACME v2", b"secret salt"));
+	});
+}
+
+#[test]
+fn it_fails_private_file_plaintext_metadata() {
+	new_test_ext().execute_with(|| {
+		let file_id = generate_file_id();
+		let commitment = Filesign::tag_commitment(b"Supply contract with ACME", b"secret salt");
+		assert_ok!(Filesign::create_private_file(Origin::signed(1), commitment, H256::from([0x66; 32]), FileIdMode::Explicit(file_id), false));
+
+		assert_noop!(Filesign::set_attribute(Origin::signed(1), file_id, b"counterparty".to_vec(), b"ACME".to_vec()),
+			RuntimeError::PrivateFileMetadata);
+		let commitment = Filesign::tag_commitment(b"Supply contract with ACME v2", b"secret salt");
+		let location = FileLocation::Url(b"https://example.com/acme.pdf".to_vec());
+		assert_noop!(Filesign::create_new_version(Origin::signed(1), file_id, commitment.as_bytes().to_vec(), H256::from([0x67; 32]), Some(location), None, false),
+			RuntimeError::PrivateFileMetadata);
+	});
+}
+
+#[test]
+fn it_works_reveal_to_and_revoke() {
+	new_test_ext_with_event().execute_with(|| {
+		let file_id = generate_file_id();
+		let commitment = Filesign::tag_commitment(b"Supply contract with ACME", b"secret salt");
+		assert_ok!(Filesign::create_private_file(Origin::signed(1), commitment, H256::from([0x66; 32]), FileIdMode::Explicit(file_id), false));
+
+		assert_ok!(Filesign::reveal_to(Origin::signed(1), file_id, 2, vec![7; 64]));
+		assert_eq!(Some(vec![7; 64]), Filesign::envelope(file_id, 2));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::RevealedTo(1, file_id, 2)), last_event().unwrap());
+
+		assert_ok!(Filesign::revoke_reveal(Origin::signed(1), file_id, 2));
+		assert_eq!(None, Filesign::envelope(file_id, 2));
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::RevealRevoked(1, file_id, 2)), last_event().unwrap());
+		assert_noop!(Filesign::revoke_reveal(Origin::signed(1), file_id, 2), RuntimeError::EnvelopeNotFound);
+	});
+}
+
+#[test]
+fn it_fails_reveal_to() {
+	new_test_ext().execute_with(|| {
+		let file_id = generate_file_id();
+		let public_id = [7; 16];
+		let commitment = Filesign::tag_commitment(b"Supply contract with ACME", b"secret salt");
+		assert_ok!(Filesign::create_private_file(Origin::signed(1), commitment, H256::from([0x66; 32]), FileIdMode::Explicit(file_id), false));
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x67; 32]), None, FileIdMode::Explicit(public_id), false));
+
+		assert_noop!(Filesign::reveal_to(Origin::signed(2), file_id, 3, vec![7; 64]), RuntimeError::AddressNotOwner);
+		assert_noop!(Filesign::reveal_to(Origin::signed(1), public_id, 3, vec![7; 64]), RuntimeError::NotPrivateFile);
+		assert_noop!(Filesign::reveal_to(Origin::signed(1), file_id, 3, vec![]), RuntimeError::InvalidEnvelope);
+		assert_noop!(Filesign::reveal_to(Origin::signed(1), file_id, 3, vec![7; MAX_ENVELOPE_LENGTH + 1]), RuntimeError::InvalidEnvelope);
+		assert_noop!(Filesign::reveal_to(Origin::signed(1), [8; 16], 3, vec![7; 64]), RuntimeError::FileNotFound);
+	});
+}
+
+#[test]
+fn it_works_salted_commitment() {
+	new_test_ext_with_event().execute_with(|| {
+		let file_id = generate_file_id();
+		let filehash = Filesign::salted_hash(b"secret salt", b"short confidential document");
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], filehash, None, FileIdMode::Explicit(file_id), true));
+		assert_eq!(SaltState::Committed, Filesign::get_file_by_id(file_id).unwrap().versions[0].salt);
+
+		// provable with the salt before it is revealed
+		assert!(Filesign::verify_salted_document(file_id, 0, b"secret salt", b"short confidential document"));
+		assert!(!Filesign::verify_salted_document(file_id, 0, b"secret salt", b"other document"));
+		assert!(!Filesign::verify_salted_document(file_id, 0, b"wrong salt", b"short confidential document"));
+
+		assert_noop!(Filesign::reveal_salt(Origin::signed(2), file_id, 0, b"secret salt".to_vec()), RuntimeError::AddressNotOwner);
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"secret salt".to_vec()));
+		assert_eq!(SaltState::Revealed(b"secret salt".to_vec()), Filesign::get_file_by_id(file_id).unwrap().versions[0].salt);
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::SaltRevealed(1, file_id, 0, b"secret salt".to_vec())), last_event().unwrap());
+		assert!(Filesign::verify_salted_document(file_id, 0, b"secret salt", b"short confidential document"));
+	});
+}
+
+#[test]
+fn it_works_correct_revealed_salt() {
+	new_test_ext_with_event().execute_with(|| {
+		let file_id = generate_file_id();
+		let filehash = Filesign::salted_hash(b"secret salt", b"short confidential document");
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], filehash, None, FileIdMode::Explicit(file_id), true));
+
+		// a wrong reveal doesn't break verification with the right salt
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"wrong salt".to_vec()));
+		assert!(Filesign::verify_salted_document(file_id, 0, b"secret salt", b"short confidential document"));
+		assert!(!Filesign::verify_salted_document(file_id, 0, b"wrong salt", b"short confidential document"));
+
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"secret salt".to_vec()));
+		assert_eq!(SaltState::Revealed(b"secret salt".to_vec()), Filesign::get_file_by_id(file_id).unwrap().versions[0].salt);
+		assert_eq!(Event::pallet_filesign(crate::RawEvent::SaltRevealed(1, file_id, 0, b"secret salt".to_vec())), last_event().unwrap());
+
+		assert_ok!(Filesign::assign_signer(Origin::signed(1), file_id, 2));
+		assert_ok!(Filesign::accept_invitation(Origin::signed(2), file_id));
+		assert_ok!(Filesign::sign_latest_version(Origin::signed(2), file_id));
+		assert_ok!(Filesign::finalize_file(Origin::signed(1), file_id));
+		assert_noop!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"wrong salt".to_vec()), RuntimeError::FileFinalized);
+	});
+}
+
+#[test]
+fn it_works_correct_revealed_salt_clears_mismatch() {
+	new_test_ext_with_event().execute_with(|| {
+		assert_ok!(Filesign::set_hash_checkers(Origin::root(), vec![7]));
+		let file_id = generate_file_id();
+		let filehash = Filesign::salted_hash(b"secret salt", b"contract");
+		let location = FileLocation::Url(b"https://example.com/contract.pdf".to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], filehash, Some(location), FileIdMode::Explicit(file_id), true));
+
+		// the document doesn't match with the wrong salt
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"wrong salt".to_vec()));
+		let payload = mismatch_payload(7, file_id, 0);
+		assert_ok!(Filesign::report_mismatch(Origin::none(), payload.clone(), TestSignature(7, payload.encode())));
+
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"secret salt".to_vec()));
+		assert_eq!(None, Filesign::mismatches(file_id, 0));
+		assert!(event_deposited(Event::pallet_filesign(crate::RawEvent::MismatchCleared(file_id, 0))));
+		assert_noop!(Filesign::report_mismatch(Origin::none(), payload.clone(), TestSignature(7, payload.encode())),
+			RuntimeError::MismatchReportOutdated);
+	});
+}
+
+#[test]
+fn it_fails_reveal_salt() {
+	new_test_ext().execute_with(|| {
+		let file_id = generate_file_id();
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], H256::from([0x66; 32]), None, FileIdMode::Explicit(file_id), false));
+		assert_ok!(Filesign::create_new_version(Origin::signed(1), file_id, vec![40, 2], H256::from([0x67; 32]), None, None, true));
+		assert_eq!(SaltState::Unsalted, Filesign::get_file_by_id(file_id).unwrap().versions[0].salt);
+		assert!(!Filesign::verify_salted_document(file_id, 0, b"secret salt", b"document"));
+
+		assert_noop!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"secret salt".to_vec()), RuntimeError::NotSalted);
+		assert_noop!(Filesign::reveal_salt(Origin::signed(1), file_id, 2, b"secret salt".to_vec()), RuntimeError::VersionNotFound);
+		assert_noop!(Filesign::reveal_salt(Origin::signed(1), file_id, 1, vec![]), RuntimeError::InvalidHashSalt);
+		assert_noop!(Filesign::reveal_salt(Origin::signed(1), file_id, 1, vec![1; MAX_HASH_SALT_LENGTH + 1]), RuntimeError::InvalidHashSalt);
+		assert_noop!(Filesign::reveal_salt(Origin::signed(1), [8; 16], 1, b"secret salt".to_vec()), RuntimeError::FileNotFound);
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 1, b"secret salt".to_vec()));
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_skips_batch_root() {
+	// any request would fail the test as none is expected
+	let (mut ext, _state, pool_state) = new_test_ext_with_offchain();
+	ext.execute_with(|| {
+		register_local_hash_checker();
+		allow_fetch_host(b"example.com");
+		let root = merkle_root(&[H256::from([1; 32]), H256::from([2; 32])]).unwrap();
+		assert_ok!(Filesign::anchor_batch(Origin::signed(1), root, 2));
+		let location = FileLocation::Url(b"https://example.com/batch.zip".to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], root, Some(location), FileIdMode::Explicit(generate_file_id()), false));
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(Some((1, 0)), hash_check_cursor());
+	});
+}
+
+#[test]
+fn it_works_offchain_worker_salted_document() {
+	let (mut ext, state, pool_state) = new_test_ext_with_offchain();
+	let uri = "https://example.com/contract.pdf";
+	let file_id = generate_file_id();
+	ext.execute_with(|| {
+		register_local_hash_checker();
+		let filehash = Filesign::salted_hash(b"secret salt", b"contract");
+		let location = FileLocation::Url(uri.as_bytes().to_vec());
+		assert_ok!(Filesign::create_new_file(Origin::signed(1), vec![40, 1], filehash, Some(location), FileIdMode::Explicit(file_id), true));
+		allow_fetch_host(b"example.com");
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		// salt is not known yet, no request
+		<Filesign as OffchainWorker<u64>>::offchain_worker(10);
+
+		state.write().expect_request(PendingRequest {
+			method: "GET".into(),
+			uri: uri.into(),
+			response: Some(b"contract".to_vec()),
+			sent: true,
+			..Default::default()
+		});
+		// revealing the salt queues the version again
+		assert_ok!(Filesign::reveal_salt(Origin::signed(1), file_id, 0, b"secret salt".to_vec()));
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		<Filesign as OffchainWorker<u64>>::offchain_worker(20);
+
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
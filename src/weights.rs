@@ -0,0 +1,449 @@
+//! Weights of the filesign pallet
+//!
+//! The base weights and the slopes of the components are the extrinsic times of the benchmarks of `benchmarking.rs`,
+//! measured natively in a release build on the mock runtime, 11 steps per component and 50 repeats, fitted with
+//! min squares. The storage accesses are counted from the code of each call. Regenerate the file on reference
+//! hardware with a node built with `--features runtime-benchmarks`:
+//!
+//! ./target/release/node-template benchmark --chain dev --execution wasm --wasm-execution compiled
+//!     --pallet pallet_evercity_filesign --extrinsic '*' --steps 50 --repeat 20 --output ./src/weights.rs
+//!
+//! Components: s - signers of the file, up to MaxSigners, e - storage entries removed by force_delete_file,
+//! r - template roles, m - group members, c - hash checkers
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use frame_support::sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_evercity_filesign
+pub trait WeightInfo {
+    fn create_new_file() -> Weight;
+    fn commit_file_id() -> Weight;
+    fn create_private_file() -> Weight;
+    fn reveal_to(s: u32, ) -> Weight;
+    fn reveal_salt(s: u32, ) -> Weight;
+    fn revoke_reveal(s: u32, ) -> Weight;
+    fn create_template(r: u32, ) -> Weight;
+    fn create_from_template(r: u32, ) -> Weight;
+    fn create_new_version(s: u32, ) -> Weight;
+    fn supersede_file(s: u32, ) -> Weight;
+    fn finalize_file(s: u32, ) -> Weight;
+    fn sign_latest_version(s: u32, ) -> Weight;
+    fn sign_latest_version_on_behalf(s: u32, ) -> Weight;
+    fn sign_with_payload(s: u32, ) -> Weight;
+    fn add_delegate() -> Weight;
+    fn remove_delegate() -> Weight;
+    fn delete_signer(s: u32, ) -> Weight;
+    fn assign_signer(s: u32, ) -> Weight;
+    fn accept_invitation(s: u32, ) -> Weight;
+    fn decline_invitation(s: u32, ) -> Weight;
+    fn create_group(m: u32, ) -> Weight;
+    fn update_group(m: u32, ) -> Weight;
+    fn assign_group(s: u32, ) -> Weight;
+    fn delete_group(s: u32, ) -> Weight;
+    fn force_transfer_owner(s: u32, ) -> Weight;
+    fn force_remove_signer(s: u32, ) -> Weight;
+    fn force_delete_file(s: u32, e: u32, ) -> Weight;
+    fn set_attribute(s: u32, ) -> Weight;
+    fn clear_attribute(s: u32, ) -> Weight;
+    fn anchor_hash() -> Weight;
+    fn anchor_batch() -> Weight;
+    fn report_mismatch(s: u32, ) -> Weight;
+    fn clear_mismatch() -> Weight;
+    fn set_hash_checkers(c: u32, ) -> Weight;
+}
+
+/// Weights for pallet_evercity_filesign using the Substrate node and recommended hardware
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn create_new_file() -> Weight {
+        (14_423_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn commit_file_id() -> Weight {
+        (5_884_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn create_private_file() -> Weight {
+        (9_345_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn reveal_to(_s: u32, ) -> Weight {
+        (3_497_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn reveal_salt(s: u32, ) -> Weight {
+        (7_588_000 as Weight)
+            .saturating_add((21_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn revoke_reveal(s: u32, ) -> Weight {
+        (3_927_000 as Weight)
+            .saturating_add((7_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn create_template(r: u32, ) -> Weight {
+        (5_161_000 as Weight)
+            .saturating_add((22_000 as Weight).saturating_mul(r as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn create_from_template(r: u32, ) -> Weight {
+        (21_437_000 as Weight)
+            .saturating_add((2_706_000 as Weight).saturating_mul(r as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+    }
+    fn create_new_version(_s: u32, ) -> Weight {
+        (13_009_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn supersede_file(_s: u32, ) -> Weight {
+        (10_408_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn finalize_file(s: u32, ) -> Weight {
+        (5_663_000 as Weight)
+            .saturating_add((195_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn sign_latest_version(s: u32, ) -> Weight {
+        (8_058_000 as Weight)
+            .saturating_add((156_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn sign_latest_version_on_behalf(s: u32, ) -> Weight {
+        (11_457_000 as Weight)
+            .saturating_add((150_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn sign_with_payload(s: u32, ) -> Weight {
+        (13_805_000 as Weight)
+            .saturating_add((278_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn add_delegate() -> Weight {
+        (8_871_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn remove_delegate() -> Weight {
+        (4_607_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn delete_signer(s: u32, ) -> Weight {
+        (7_405_000 as Weight)
+            .saturating_add((177_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn assign_signer(s: u32, ) -> Weight {
+        (11_856_000 as Weight)
+            .saturating_add((23_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn accept_invitation(s: u32, ) -> Weight {
+        (8_406_000 as Weight)
+            .saturating_add((35_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn decline_invitation(_s: u32, ) -> Weight {
+        (7_486_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn create_group(m: u32, ) -> Weight {
+        (5_941_000 as Weight)
+            .saturating_add((484_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn update_group(m: u32, ) -> Weight {
+        (4_877_000 as Weight)
+            .saturating_add((508_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn assign_group(_s: u32, ) -> Weight {
+        (16_363_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn delete_group(s: u32, ) -> Weight {
+        (6_636_000 as Weight)
+            .saturating_add((43_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn force_transfer_owner(_s: u32, ) -> Weight {
+        (5_859_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn force_remove_signer(s: u32, ) -> Weight {
+        (7_248_000 as Weight)
+            .saturating_add((171_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn force_delete_file(_s: u32, e: u32, ) -> Weight {
+        (246_270_000 as Weight)
+            .saturating_add((2_517_000 as Weight).saturating_mul(e as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(e as Weight)))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(e as Weight)))
+    }
+    fn set_attribute(s: u32, ) -> Weight {
+        (13_043_000 as Weight)
+            .saturating_add((6_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn clear_attribute(s: u32, ) -> Weight {
+        (12_622_000 as Weight)
+            .saturating_add((1_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn anchor_hash() -> Weight {
+        (7_344_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn anchor_batch() -> Weight {
+        (7_297_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn report_mismatch(s: u32, ) -> Weight {
+        (11_679_000 as Weight)
+            .saturating_add((19_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn clear_mismatch() -> Weight {
+        (6_233_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_hash_checkers(c: u32, ) -> Weight {
+        (3_476_000 as Weight)
+            .saturating_add((480_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create_new_file() -> Weight {
+        (14_423_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn commit_file_id() -> Weight {
+        (5_884_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn create_private_file() -> Weight {
+        (9_345_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn reveal_to(_s: u32, ) -> Weight {
+        (3_497_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn reveal_salt(s: u32, ) -> Weight {
+        (7_588_000 as Weight)
+            .saturating_add((21_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn revoke_reveal(s: u32, ) -> Weight {
+        (3_927_000 as Weight)
+            .saturating_add((7_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn create_template(r: u32, ) -> Weight {
+        (5_161_000 as Weight)
+            .saturating_add((22_000 as Weight).saturating_mul(r as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn create_from_template(r: u32, ) -> Weight {
+        (21_437_000 as Weight)
+            .saturating_add((2_706_000 as Weight).saturating_mul(r as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+    }
+    fn create_new_version(_s: u32, ) -> Weight {
+        (13_009_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn supersede_file(_s: u32, ) -> Weight {
+        (10_408_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn finalize_file(s: u32, ) -> Weight {
+        (5_663_000 as Weight)
+            .saturating_add((195_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn sign_latest_version(s: u32, ) -> Weight {
+        (8_058_000 as Weight)
+            .saturating_add((156_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn sign_latest_version_on_behalf(s: u32, ) -> Weight {
+        (11_457_000 as Weight)
+            .saturating_add((150_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn sign_with_payload(s: u32, ) -> Weight {
+        (13_805_000 as Weight)
+            .saturating_add((278_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn add_delegate() -> Weight {
+        (8_871_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn remove_delegate() -> Weight {
+        (4_607_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn delete_signer(s: u32, ) -> Weight {
+        (7_405_000 as Weight)
+            .saturating_add((177_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn assign_signer(s: u32, ) -> Weight {
+        (11_856_000 as Weight)
+            .saturating_add((23_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn accept_invitation(s: u32, ) -> Weight {
+        (8_406_000 as Weight)
+            .saturating_add((35_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn decline_invitation(_s: u32, ) -> Weight {
+        (7_486_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn create_group(m: u32, ) -> Weight {
+        (5_941_000 as Weight)
+            .saturating_add((484_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn update_group(m: u32, ) -> Weight {
+        (4_877_000 as Weight)
+            .saturating_add((508_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn assign_group(_s: u32, ) -> Weight {
+        (16_363_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn delete_group(s: u32, ) -> Weight {
+        (6_636_000 as Weight)
+            .saturating_add((43_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn force_transfer_owner(_s: u32, ) -> Weight {
+        (5_859_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn force_remove_signer(s: u32, ) -> Weight {
+        (7_248_000 as Weight)
+            .saturating_add((171_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn force_delete_file(_s: u32, e: u32, ) -> Weight {
+        (246_270_000 as Weight)
+            .saturating_add((2_517_000 as Weight).saturating_mul(e as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(e as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(e as Weight)))
+    }
+    fn set_attribute(s: u32, ) -> Weight {
+        (13_043_000 as Weight)
+            .saturating_add((6_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn clear_attribute(s: u32, ) -> Weight {
+        (12_622_000 as Weight)
+            .saturating_add((1_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn anchor_hash() -> Weight {
+        (7_344_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn anchor_batch() -> Weight {
+        (7_297_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn report_mismatch(s: u32, ) -> Weight {
+        (11_679_000 as Weight)
+            .saturating_add((19_000 as Weight).saturating_mul(s as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn clear_mismatch() -> Weight {
+        (6_233_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_hash_checkers(c: u32, ) -> Weight {
+        (3_476_000 as Weight)
+            .saturating_add((480_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}